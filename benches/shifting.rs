@@ -0,0 +1,225 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015-2016 Nick Stevens <nick@bitcurry.com>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Benchmarks for the element-shifting paths of `FixedVec`.
+//!
+//! Each benchmark is paired with a `loop_` version running the bounds-checked,
+//! element-by-element code that `FixedVec` used before, for comparison.
+//! Requires a nightly compiler:
+//!
+//! ```text
+//! cargo +nightly bench --features unstable
+//! ```
+
+#![cfg(feature = "unstable")]
+#![feature(test)]
+
+extern crate fixedvec;
+extern crate test;
+
+use fixedvec::FixedVec;
+use test::{black_box, Bencher};
+
+/// The shifting code of `FixedVec` before it moved to `ptr::copy`, copied
+/// verbatim: bounds-checked indexing, one element at a time.
+struct Baseline<'a> {
+    memory: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> Baseline<'a> {
+    fn push(&mut self, value: u8) -> Result<(), ()> {
+        if self.available() >= 1 {
+            self.memory[self.len] = value;
+            self.len += 1;
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
+    fn available(&self) -> usize {
+        self.memory.len() - self.len
+    }
+
+    // Like the original, this starts one slot past the last element, so the
+    // buffer needs a spare slot beyond the capacity in use.
+    fn insert(&mut self, index: usize, element: u8) -> Result<(), ()> {
+        assert!(index <= self.len);
+        if index == self.len || self.len == 0 {
+            self.push(element)
+        } else if self.available() >= 1 {
+            self.len += 1;
+            let mut i = self.len;
+            loop {
+                if i == index {
+                    break;
+                }
+                self.memory[i] = self.memory[i - 1];
+                i -= 1;
+            }
+            self.memory[index] = element;
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
+    fn remove(&mut self, index: usize) -> u8 {
+        assert!(index < self.len);
+        let ret = self.memory[index];
+        self.len -= 1;
+        for i in index..self.len {
+            self.memory[i] = self.memory[i + 1];
+        }
+        ret
+    }
+
+    fn retain<F>(&mut self, f: F)
+    where
+        F: Fn(&u8) -> bool,
+    {
+        let mut head: usize = 0;
+        let mut tail: usize = 0;
+        loop {
+            if head >= self.len {
+                break;
+            }
+            if f(&self.memory[head]) {
+                self.memory[tail] = self.memory[head];
+                tail += 1;
+            }
+            head += 1;
+        }
+        self.len = tail;
+    }
+}
+
+fn insert_remove(b: &mut Bencher, size: usize) {
+    let mut space = vec![0u8; size + 1];
+    let mut vec = FixedVec::new(&mut space);
+    vec.resize(size, 1);
+    b.iter(|| {
+        vec.insert(0, black_box(2)).unwrap();
+        black_box(vec.remove(0))
+    });
+}
+
+fn loop_insert_remove(b: &mut Bencher, size: usize) {
+    let mut space = vec![1u8; size + 2];
+    let mut vec = Baseline {
+        memory: &mut space,
+        len: size,
+    };
+    b.iter(|| {
+        vec.insert(0, black_box(2)).unwrap();
+        black_box(vec.remove(0))
+    });
+}
+
+fn retain(b: &mut Bencher, size: usize) {
+    let data: Vec<u8> = (0..size).map(|i| i as u8).collect();
+    let mut space = vec![0u8; size];
+    let mut vec = FixedVec::new(&mut space);
+    b.iter(|| {
+        // Every slot of a `[u8]` holds a valid `u8`
+        unsafe { vec.set_len(size) };
+        vec.copy_from_slice(&data);
+        vec.retain(|&x| x % 3 != 0);
+        black_box(vec.len())
+    });
+}
+
+fn loop_retain_bench(b: &mut Bencher, size: usize) {
+    let data: Vec<u8> = (0..size).map(|i| i as u8).collect();
+    let mut space = vec![0u8; size];
+    let mut vec = Baseline {
+        memory: &mut space,
+        len: 0,
+    };
+    b.iter(|| {
+        vec.len = size;
+        vec.memory.copy_from_slice(&data);
+        vec.retain(|&x| x % 3 != 0);
+        black_box(vec.len)
+    });
+}
+
+#[bench]
+fn insert_remove_64(b: &mut Bencher) {
+    insert_remove(b, 64);
+}
+
+#[bench]
+fn insert_remove_512(b: &mut Bencher) {
+    insert_remove(b, 512);
+}
+
+#[bench]
+fn insert_remove_4096(b: &mut Bencher) {
+    insert_remove(b, 4096);
+}
+
+#[bench]
+fn loop_insert_remove_64(b: &mut Bencher) {
+    loop_insert_remove(b, 64);
+}
+
+#[bench]
+fn loop_insert_remove_512(b: &mut Bencher) {
+    loop_insert_remove(b, 512);
+}
+
+#[bench]
+fn loop_insert_remove_4096(b: &mut Bencher) {
+    loop_insert_remove(b, 4096);
+}
+
+#[bench]
+fn retain_64(b: &mut Bencher) {
+    retain(b, 64);
+}
+
+#[bench]
+fn retain_512(b: &mut Bencher) {
+    retain(b, 512);
+}
+
+#[bench]
+fn retain_4096(b: &mut Bencher) {
+    retain(b, 4096);
+}
+
+#[bench]
+fn loop_retain_64(b: &mut Bencher) {
+    loop_retain_bench(b, 64);
+}
+
+#[bench]
+fn loop_retain_512(b: &mut Bencher) {
+    loop_retain_bench(b, 512);
+}
+
+#[bench]
+fn loop_retain_4096(b: &mut Bencher) {
+    loop_retain_bench(b, 4096);
+}
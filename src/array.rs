@@ -0,0 +1,366 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015-2016 Nick Stevens <nick@bitcurry.com>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Owned, inline-storage version of `FixedVec`.
+
+use core::borrow::{Borrow, BorrowMut};
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::ops;
+
+use super::{FixedVec, IntoIter, Iter, IterMut};
+
+/// A `FixedVec` that owns its backing memory.
+///
+/// `ArrayFixedVec` stores up to `N` elements inline, so it can be returned
+/// from functions, stored in structs without a lifetime, or placed in a
+/// `static`. It dereferences to a `FixedVec` backed by an array of
+/// `MaybeUninit<T>`, so the full `FixedVec` API is available, and can lend
+/// itself out as a slice-backed `FixedVec` through `with_fixed_vec`.
+///
+/// # Example
+///
+/// ```
+/// # use fixedvec::ArrayFixedVec;
+/// fn make() -> ArrayFixedVec<u8, 8> {
+///     let mut vec = ArrayFixedVec::new();
+///     vec.push_all(&[1, 2, 3]).unwrap();
+///     vec
+/// }
+///
+/// let vec = make();
+/// assert_eq!(vec.capacity(), 8);
+/// assert_eq!(vec.as_slice(), &[1, 2, 3]);
+/// ```
+pub struct ArrayFixedVec<T, const N: usize>(FixedVec<'static, T, [MaybeUninit<T>; N]>);
+
+impl<T, const N: usize> ArrayFixedVec<T, N> {
+    /// Creates a new, empty `ArrayFixedVec`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::ArrayFixedVec;
+    /// let vec: ArrayFixedVec<u8, 16> = ArrayFixedVec::new();
+    /// assert_eq!(vec.capacity(), 16);
+    /// assert_eq!(vec.len(), 0);
+    /// ```
+    pub const fn new() -> Self {
+        ArrayFixedVec(FixedVec {
+            // An array of `MaybeUninit` does not require initialization.
+            memory: unsafe { MaybeUninit::uninit().assume_init() },
+            len: 0,
+            marker: PhantomData,
+        })
+    }
+
+    /// Lends the vector out as a slice-backed `FixedVec` for the duration of
+    /// `f`.
+    ///
+    /// This allows an `ArrayFixedVec` to be passed to code written against a
+    /// borrowed `FixedVec`. Any changes made through the borrowed view are
+    /// kept.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::{ArrayFixedVec, FixedVec};
+    /// use std::mem::MaybeUninit;
+    ///
+    /// fn fill(vec: &mut FixedVec<u8, &mut [MaybeUninit<u8>]>) {
+    ///     vec.push_all(&[1, 2, 3]).unwrap();
+    /// }
+    ///
+    /// let mut vec: ArrayFixedVec<u8, 8> = ArrayFixedVec::new();
+    /// vec.with_fixed_vec(fill);
+    /// assert_eq!(vec.as_slice(), &[1, 2, 3]);
+    /// ```
+    pub fn with_fixed_vec<R, F>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut FixedVec<T, &mut [MaybeUninit<T>]>) -> R,
+    {
+        // The elements belong to the borrowed view until `f` returns. If `f`
+        // panics, the view drops them and this vector is left empty.
+        let len = self.0.len;
+        self.0.len = 0;
+        let mut vec = FixedVec::from_uninit(&mut self.0.memory);
+        vec.len = len;
+        let ret = f(&mut vec);
+        self.0.len = vec.len;
+        vec.len = 0;
+        ret
+    }
+}
+
+impl<T, const N: usize> ops::Deref for ArrayFixedVec<T, N> {
+    type Target = FixedVec<'static, T, [MaybeUninit<T>; N]>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, const N: usize> ops::DerefMut for ArrayFixedVec<T, N> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T, const N: usize> AsRef<[T]> for ArrayFixedVec<T, N> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> AsMut<[T]> for ArrayFixedVec<T, N> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T, const N: usize> Borrow<[T]> for ArrayFixedVec<T, N> {
+    #[inline]
+    fn borrow(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> BorrowMut<[T]> for ArrayFixedVec<T, N> {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T, const N: usize> Default for ArrayFixedVec<T, N> {
+    fn default() -> Self {
+        ArrayFixedVec::new()
+    }
+}
+
+impl<T, const N: usize> Clone for ArrayFixedVec<T, N>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        let mut vec = ArrayFixedVec::new();
+        vec.push_all(self.as_slice()).unwrap();
+        vec
+    }
+}
+
+impl<T, const N: usize> fmt::Debug for ArrayFixedVec<T, N>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl<T, const N: usize> IntoIterator for ArrayFixedVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<'static, T, [MaybeUninit<T>; N]>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'b, T, const N: usize> IntoIterator for &'b ArrayFixedVec<T, N> {
+    type Item = &'b T;
+    type IntoIter = Iter<'b, T>;
+
+    fn into_iter(self) -> Iter<'b, T> {
+        self.iter()
+    }
+}
+
+impl<'b, T, const N: usize> IntoIterator for &'b mut ArrayFixedVec<T, N> {
+    type Item = &'b mut T;
+    type IntoIter = IterMut<'b, T>;
+
+    fn into_iter(self) -> IterMut<'b, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T, const N: usize> Hash for ArrayFixedVec<T, N>
+where
+    T: Hash,
+{
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Hash::hash(&self.0, state)
+    }
+}
+
+impl<T, const N: usize> Extend<T> for ArrayFixedVec<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iterable: I) {
+        self.0.extend(iterable)
+    }
+}
+
+impl<T, U, const N: usize, const M: usize> PartialEq<ArrayFixedVec<U, M>> for ArrayFixedVec<T, N>
+where
+    T: PartialEq<U>,
+{
+    fn eq(&self, other: &ArrayFixedVec<U, M>) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T, const N: usize> Eq for ArrayFixedVec<T, N> where T: Eq {}
+
+impl<T, U, const N: usize> PartialEq<[U]> for ArrayFixedVec<T, N>
+where
+    T: PartialEq<U>,
+{
+    #[inline]
+    fn eq(&self, other: &[U]) -> bool {
+        self.0 == *other
+    }
+}
+
+impl<'b, T, U, const N: usize> PartialEq<&'b [U]> for ArrayFixedVec<T, N>
+where
+    T: PartialEq<U>,
+{
+    #[inline]
+    fn eq(&self, other: &&'b [U]) -> bool {
+        self.0 == *other
+    }
+}
+
+impl<T, U, const N: usize, const M: usize> PartialEq<[U; M]> for ArrayFixedVec<T, N>
+where
+    T: PartialEq<U>,
+{
+    #[inline]
+    fn eq(&self, other: &[U; M]) -> bool {
+        self.0 == *other
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, U, const N: usize> PartialEq<alloc::vec::Vec<U>> for ArrayFixedVec<T, N>
+where
+    T: PartialEq<U>,
+{
+    #[inline]
+    fn eq(&self, other: &alloc::vec::Vec<U>) -> bool {
+        self.0 == *other
+    }
+}
+
+impl<T, const N: usize, const M: usize> PartialOrd<ArrayFixedVec<T, M>> for ArrayFixedVec<T, N>
+where
+    T: PartialOrd,
+{
+    #[inline]
+    fn partial_cmp(&self, other: &ArrayFixedVec<T, M>) -> Option<Ordering> {
+        self.0.partial_cmp(&other.0)
+    }
+}
+
+impl<T, const N: usize> Ord for ArrayFixedVec<T, N>
+where
+    T: Ord,
+{
+    #[inline]
+    fn cmp(&self, other: &ArrayFixedVec<T, N>) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::test_util::Droppable;
+    use super::ArrayFixedVec;
+    use std::cell::Cell;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn test_api_parity() {
+        let mut vec: ArrayFixedVec<u8, 6> = ArrayFixedVec::new();
+        vec.push_all(&[3, 1, 1, 2]).unwrap();
+        vec.insert(0, 4).unwrap();
+        vec.dedup();
+        assert_eq!(vec.as_slice(), &[4, 3, 1, 2]);
+        vec.retain(|&x| x != 3);
+        assert_eq!(vec.swap_remove(0), 4);
+        assert_eq!(vec.as_slice(), &[2, 1]);
+        vec.extend(5..10);
+        assert_eq!(vec.as_slice(), &[2, 1, 5, 6, 7, 8]);
+        assert!(vec.push(9).is_err());
+        assert_eq!(vec.remove(1), 1);
+        assert_eq!(vec.pop(), Some(8));
+        assert_eq!(vec[0], 2);
+        assert_eq!(vec.clone(), vec);
+        assert_eq!(vec, [2, 5, 6, 7]);
+
+        let mut bigger: ArrayFixedVec<u8, 8> = ArrayFixedVec::new();
+        bigger.push_all(&[2, 5, 6, 7]).unwrap();
+        assert_eq!(vec, bigger);
+        bigger.push(0).unwrap();
+        assert!(vec < bigger);
+    }
+
+    #[test]
+    fn test_drop() {
+        let drops = Cell::new(0);
+        {
+            let mut vec: ArrayFixedVec<Droppable, 4> = ArrayFixedVec::new();
+            vec.push(Droppable(1, &drops)).unwrap();
+            vec.push(Droppable(2, &drops)).unwrap();
+            vec.push(Droppable(3, &drops)).unwrap();
+            assert_eq!(vec.pop().map(|d| d.0), Some(3));
+            assert_eq!(drops.get(), 1);
+        }
+        assert_eq!(drops.get(), 3);
+    }
+
+    #[test]
+    fn test_with_fixed_vec_panic() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let drops = Cell::new(0);
+        let mut vec: ArrayFixedVec<Droppable, 4> = ArrayFixedVec::new();
+        vec.push(Droppable(1, &drops)).unwrap();
+        vec.push(Droppable(2, &drops)).unwrap();
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            vec.with_fixed_vec(|_| std::panic!("boom"));
+        }));
+        assert!(result.is_err());
+        assert_eq!(drops.get(), 2);
+        assert!(vec.is_empty());
+        drop(vec);
+        assert_eq!(drops.get(), 2);
+    }
+}
@@ -0,0 +1,922 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015-2016 Nick Stevens <nick@bitcurry.com>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Heapless double-ended queue.
+
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::ops;
+use core::ptr;
+use core::slice;
+
+use super::{CapacityError, Storage};
+
+/// A double-ended queue backed by a fixed buffer.
+///
+/// `FixedDeque` is a ring buffer: elements can be pushed and popped at both
+/// ends in O(1), and the live elements may wrap around the end of the
+/// buffer. Like `FixedVec`, it works over any `Storage`, and the default is a
+/// borrowed slice.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate fixedvec;
+/// # use fixedvec::FixedDeque;
+/// # fn main() {
+/// let mut space = alloc_stack!([u8; 4]);
+/// let mut queue = FixedDeque::new(&mut space);
+///
+/// queue.push_back(1).unwrap();
+/// queue.push_back(2).unwrap();
+/// assert_eq!(queue.pop_front(), Some(1));
+/// queue.push_back(3).unwrap();
+/// assert_eq!(queue.iter().collect::<Vec<_>>(), vec![&2, &3]);
+/// # }
+/// ```
+pub struct FixedDeque<'a, T, S = &'a mut [T]>
+where
+    S: Storage<T>,
+{
+    memory: S,
+    /// Slot of the front element.
+    head: usize,
+    len: usize,
+    marker: PhantomData<(&'a (), T)>,
+}
+
+impl<'a, T> FixedDeque<'a, T>
+where
+    T: 'a + Copy,
+{
+    /// Create a new, empty `FixedDeque` from the provided slice.
+    ///
+    /// Only `Copy` types can be stored in a plain slice. Use `from_uninit`
+    /// for other types.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 16]);
+    /// let queue = FixedDeque::new(&mut space);
+    /// assert_eq!(queue.capacity(), 16);
+    /// assert!(queue.is_empty());
+    /// # }
+    /// ```
+    pub fn new(memory: &'a mut [T]) -> Self {
+        FixedDeque::from_storage(memory)
+    }
+}
+
+impl<'a, T> FixedDeque<'a, T, &'a mut [MaybeUninit<T>]> {
+    /// Create a new, empty `FixedDeque` from a slice of possibly-uninitialized
+    /// memory.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!(uninit [String; 4]);
+    /// let mut queue = FixedDeque::from_uninit(&mut space);
+    /// queue.push_back("hello".to_string()).unwrap();
+    /// assert_eq!(queue.front().unwrap(), "hello");
+    /// # }
+    /// ```
+    pub fn from_uninit(memory: &'a mut [MaybeUninit<T>]) -> Self {
+        FixedDeque::from_storage(memory)
+    }
+}
+
+impl<'a, T, S> FixedDeque<'a, T, S>
+where
+    S: Storage<T>,
+{
+    /// Create a new, empty `FixedDeque` from any kind of `Storage`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedDeque;
+    /// let mut queue = FixedDeque::from_storage([0u8; 4]);
+    /// queue.push_front(1).unwrap();
+    /// assert_eq!(queue.len(), 1);
+    /// ```
+    pub const fn from_storage(memory: S) -> Self {
+        FixedDeque {
+            memory,
+            head: 0,
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn as_ptr(&self) -> *const T {
+        self.memory.as_ptr()
+    }
+
+    #[inline]
+    fn as_mut_ptr(&mut self) -> *mut T {
+        self.memory.as_mut_ptr()
+    }
+
+    /// Returns the slot that holds the element at `index`, which must be at
+    /// most `capacity()`.
+    #[inline]
+    fn slot(&self, index: usize) -> usize {
+        let slot = self.head + index;
+        let capacity = self.capacity();
+        if slot >= capacity {
+            slot - capacity
+        } else {
+            slot
+        }
+    }
+
+    /// Returns the capacity of the queue.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 16]);
+    /// let queue = FixedDeque::new(&mut space);
+    /// assert_eq!(queue.capacity(), 16);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.memory.capacity()
+    }
+
+    /// Returns the number of elements in the queue.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 16]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_back(1).unwrap();
+    /// queue.push_front(2).unwrap();
+    /// assert_eq!(queue.len(), 2);
+    /// # }
+    /// ```
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the number of available elements in the queue. Adding more
+    /// than this number of elements (without removing some elements) will
+    /// cause further calls to element-adding functions to fail.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 16]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_back(1).unwrap();
+    /// assert_eq!(queue.available(), 15);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn available(&self) -> usize {
+        self.capacity() - self.len
+    }
+
+    /// Returns `true` if the queue contains no elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 16]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// assert!(queue.is_empty());
+    /// queue.push_back(1).unwrap();
+    /// assert!(!queue.is_empty());
+    /// # }
+    /// ```
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the queue has no space left for more elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 1]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// assert!(!queue.is_full());
+    /// queue.push_back(1).unwrap();
+    /// assert!(queue.is_full());
+    /// # }
+    /// ```
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len == self.capacity()
+    }
+
+    /// Appends an element to the back of the queue.
+    ///
+    /// If the queue is full, `value` is handed back in the error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 2]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_back(1).unwrap();
+    /// queue.push_back(2).unwrap();
+    /// assert_eq!(queue.push_back(3).unwrap_err().into_element(), 3);
+    /// assert_eq!(queue.back(), Some(&2));
+    /// # }
+    /// ```
+    pub fn push_back(&mut self, value: T) -> Result<(), CapacityError<T>> {
+        if self.is_full() {
+            return Err(CapacityError::new(value));
+        }
+        let slot = self.slot(self.len);
+        unsafe { ptr::write(self.as_mut_ptr().add(slot), value) };
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Prepends an element to the front of the queue.
+    ///
+    /// If the queue is full, `value` is handed back in the error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 2]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_front(1).unwrap();
+    /// queue.push_front(2).unwrap();
+    /// assert!(queue.push_front(3).is_err());
+    /// assert_eq!(queue.front(), Some(&2));
+    /// # }
+    /// ```
+    pub fn push_front(&mut self, value: T) -> Result<(), CapacityError<T>> {
+        if self.is_full() {
+            return Err(CapacityError::new(value));
+        }
+        self.head = if self.head == 0 {
+            self.capacity() - 1
+        } else {
+            self.head - 1
+        };
+        let head = self.head;
+        unsafe { ptr::write(self.as_mut_ptr().add(head), value) };
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Removes the first element and returns it, or `None` if the queue is
+    /// empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_back(1).unwrap();
+    /// queue.push_back(2).unwrap();
+    /// assert_eq!(queue.pop_front(), Some(1));
+    /// assert_eq!(queue.pop_front(), Some(2));
+    /// assert_eq!(queue.pop_front(), None);
+    /// # }
+    /// ```
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        let head = self.head;
+        self.head = self.slot(1);
+        self.len -= 1;
+        Some(unsafe { ptr::read(self.as_ptr().add(head)) })
+    }
+
+    /// Removes the last element and returns it, or `None` if the queue is
+    /// empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_back(1).unwrap();
+    /// queue.push_back(2).unwrap();
+    /// assert_eq!(queue.pop_back(), Some(2));
+    /// assert_eq!(queue.pop_back(), Some(1));
+    /// assert_eq!(queue.pop_back(), None);
+    /// # }
+    /// ```
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let slot = self.slot(self.len);
+        Some(unsafe { ptr::read(self.as_ptr().add(slot)) })
+    }
+
+    /// Returns a reference to the element at the given index from the front,
+    /// or `None` if the index is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_back(2).unwrap();
+    /// queue.push_front(1).unwrap();
+    /// assert_eq!(queue.get(1), Some(&2));
+    /// assert_eq!(queue.get(2), None);
+    /// # }
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len {
+            Some(unsafe { &*self.as_ptr().add(self.slot(index)) })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the element at the given index from the
+    /// front, or `None` if the index is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_back(1).unwrap();
+    /// *queue.get_mut(0).unwrap() = 5;
+    /// assert_eq!(queue.front(), Some(&5));
+    /// # }
+    /// ```
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.len {
+            let slot = self.slot(index);
+            Some(unsafe { &mut *self.as_mut_ptr().add(slot) })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a reference to the first element, or `None` if the queue is
+    /// empty.
+    #[inline]
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// Returns a mutable reference to the first element, or `None` if the
+    /// queue is empty.
+    #[inline]
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(0)
+    }
+
+    /// Returns a reference to the last element, or `None` if the queue is
+    /// empty.
+    #[inline]
+    pub fn back(&self) -> Option<&T> {
+        self.len.checked_sub(1).and_then(|i| self.get(i))
+    }
+
+    /// Returns a mutable reference to the last element, or `None` if the
+    /// queue is empty.
+    #[inline]
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        match self.len.checked_sub(1) {
+            Some(i) => self.get_mut(i),
+            None => None,
+        }
+    }
+
+    /// Clears the queue, removing all values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_back(1).unwrap();
+    /// queue.clear();
+    /// assert!(queue.is_empty());
+    /// # }
+    /// ```
+    pub fn clear(&mut self) {
+        let (front, back) = self.as_mut_slices();
+        let (front, back) = (front as *mut [T], back as *mut [T]);
+        // Elements are leaked rather than double-dropped if a drop panics.
+        self.head = 0;
+        self.len = 0;
+        unsafe {
+            ptr::drop_in_place(front);
+            ptr::drop_in_place(back);
+        }
+    }
+
+    /// Returns the elements of the queue, in order, as two slices.
+    ///
+    /// The second slice is empty unless the elements wrap around the end of
+    /// the buffer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_back(2).unwrap();
+    /// queue.push_back(3).unwrap();
+    /// queue.push_front(1).unwrap();
+    /// assert_eq!(queue.as_slices(), (&[1][..], &[2, 3][..]));
+    /// # }
+    /// ```
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let p = self.as_ptr();
+        let first = self.len.min(self.capacity() - self.head);
+        unsafe {
+            (
+                slice::from_raw_parts(p.add(self.head), first),
+                slice::from_raw_parts(p, self.len - first),
+            )
+        }
+    }
+
+    /// Returns the elements of the queue, in order, as two mutable slices.
+    ///
+    /// The second slice is empty unless the elements wrap around the end of
+    /// the buffer.
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let head = self.head;
+        let len = self.len;
+        let first = len.min(self.capacity() - head);
+        let p = self.as_mut_ptr();
+        unsafe {
+            (
+                slice::from_raw_parts_mut(p.add(head), first),
+                slice::from_raw_parts_mut(p, len - first),
+            )
+        }
+    }
+
+    /// Rearranges the buffer so that the elements are contiguous, and returns
+    /// them as a single mutable slice.
+    ///
+    /// Elements that do not wrap around the end of the buffer are already
+    /// contiguous, and are returned without being moved.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_back(2).unwrap();
+    /// queue.push_front(1).unwrap();
+    /// queue.make_contiguous().sort();
+    /// assert_eq!(queue.as_slices(), (&[1, 2][..], &[][..]));
+    /// # }
+    /// ```
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        let head = self.head;
+        let capacity = self.capacity();
+        if self.len > capacity - head {
+            // The buffer is rotated as raw slots, so uninitialized slots are
+            // never read as `T`.
+            unsafe {
+                let p = self.as_mut_ptr() as *mut MaybeUninit<T>;
+                slice::from_raw_parts_mut(p, capacity).rotate_left(head);
+            }
+            self.head = 0;
+        }
+        self.as_mut_slices().0
+    }
+
+    /// Provides a front-to-back iterator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_back(2).unwrap();
+    /// queue.push_front(1).unwrap();
+    /// let mut iter = queue.iter();
+    /// assert_eq!(iter.next(), Some(&1));
+    /// assert_eq!(iter.next(), Some(&2));
+    /// assert_eq!(iter.next(), None);
+    /// # }
+    /// ```
+    pub fn iter(&self) -> DequeIter<'_, T> {
+        let (front, back) = self.as_slices();
+        DequeIter {
+            front: front.iter(),
+            back: back.iter(),
+        }
+    }
+
+    /// Provides a front-to-back iterator that returns mutable references.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedDeque;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut queue = FixedDeque::new(&mut space);
+    /// queue.push_back(2).unwrap();
+    /// queue.push_front(1).unwrap();
+    /// for x in queue.iter_mut() {
+    ///     *x *= 10;
+    /// }
+    /// assert_eq!(queue.as_slices(), (&[10][..], &[20][..]));
+    /// # }
+    /// ```
+    pub fn iter_mut(&mut self) -> DequeIterMut<'_, T> {
+        let (front, back) = self.as_mut_slices();
+        DequeIterMut {
+            front: front.iter_mut(),
+            back: back.iter_mut(),
+        }
+    }
+}
+
+impl<'a, T, S> Drop for FixedDeque<'a, T, S>
+where
+    S: Storage<T>,
+{
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<'a, T, S> fmt::Debug for FixedDeque<'a, T, S>
+where
+    T: fmt::Debug,
+    S: Storage<T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T, S> ops::Index<usize> for FixedDeque<'a, T, S>
+where
+    S: Storage<T>,
+{
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("index out of bounds")
+    }
+}
+
+impl<'a, T, S> ops::IndexMut<usize> for FixedDeque<'a, T, S>
+where
+    S: Storage<T>,
+{
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("index out of bounds")
+    }
+}
+
+impl<'a, 'b, T, U, S, S2> PartialEq<FixedDeque<'b, U, S2>> for FixedDeque<'a, T, S>
+where
+    T: PartialEq<U>,
+    S: Storage<T>,
+    S2: Storage<U>,
+{
+    fn eq(&self, other: &FixedDeque<'b, U, S2>) -> bool {
+        self.len == other.len && self.iter().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+impl<'a, T, S> Eq for FixedDeque<'a, T, S>
+where
+    T: Eq,
+    S: Storage<T>,
+{
+}
+
+impl<'a, 'b, T, S> IntoIterator for &'b FixedDeque<'a, T, S>
+where
+    S: Storage<T>,
+{
+    type Item = &'b T;
+    type IntoIter = DequeIter<'b, T>;
+
+    fn into_iter(self) -> DequeIter<'b, T> {
+        self.iter()
+    }
+}
+
+impl<'a, 'b, T, S> IntoIterator for &'b mut FixedDeque<'a, T, S>
+where
+    S: Storage<T>,
+{
+    type Item = &'b mut T;
+    type IntoIter = DequeIterMut<'b, T>;
+
+    fn into_iter(self) -> DequeIterMut<'b, T> {
+        self.iter_mut()
+    }
+}
+
+/// A front-to-back iterator over the elements of a `FixedDeque`.
+///
+/// This struct is created by `FixedDeque::iter`.
+pub struct DequeIter<'b, T: 'b> {
+    front: slice::Iter<'b, T>,
+    back: slice::Iter<'b, T>,
+}
+
+impl<'b, T> Clone for DequeIter<'b, T> {
+    fn clone(&self) -> Self {
+        DequeIter {
+            front: self.front.clone(),
+            back: self.back.clone(),
+        }
+    }
+}
+
+impl<'b, T> Iterator for DequeIter<'b, T> {
+    type Item = &'b T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'b T> {
+        match self.front.next() {
+            Some(item) => Some(item),
+            None => self.back.next(),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.front.len() + self.back.len();
+        (len, Some(len))
+    }
+}
+
+impl<'b, T> DoubleEndedIterator for DequeIter<'b, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'b T> {
+        match self.back.next_back() {
+            Some(item) => Some(item),
+            None => self.front.next_back(),
+        }
+    }
+}
+
+impl<'b, T> ExactSizeIterator for DequeIter<'b, T> {}
+
+/// A front-to-back iterator over mutable references to the elements of a
+/// `FixedDeque`.
+///
+/// This struct is created by `FixedDeque::iter_mut`.
+pub struct DequeIterMut<'b, T: 'b> {
+    front: slice::IterMut<'b, T>,
+    back: slice::IterMut<'b, T>,
+}
+
+impl<'b, T> Iterator for DequeIterMut<'b, T> {
+    type Item = &'b mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'b mut T> {
+        match self.front.next() {
+            Some(item) => Some(item),
+            None => self.back.next(),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.front.len() + self.back.len();
+        (len, Some(len))
+    }
+}
+
+impl<'b, T> DoubleEndedIterator for DequeIterMut<'b, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'b mut T> {
+        match self.back.next_back() {
+            Some(item) => Some(item),
+            None => self.front.next_back(),
+        }
+    }
+}
+
+impl<'b, T> ExactSizeIterator for DequeIterMut<'b, T> {}
+
+#[cfg(test)]
+mod test {
+    use super::super::test_util::Droppable;
+    use super::FixedDeque;
+    use std::cell::Cell;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn test_wrap_around() {
+        let mut space = alloc_stack!([u8; 4]);
+        let mut queue = FixedDeque::new(&mut space);
+        for i in 0..10 {
+            queue.push_back(i).unwrap();
+            if queue.len() == 3 {
+                assert_eq!(queue.pop_front(), Some(i - 2));
+            }
+        }
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue[0], 8);
+        assert_eq!(queue[1], 9);
+        let items: Vec<u8> = queue.iter().rev().cloned().collect();
+        assert_eq!(items, &[9, 8]);
+    }
+
+    #[test]
+    fn test_both_ends() {
+        let mut space = alloc_stack!([u8; 4]);
+        let mut queue = FixedDeque::new(&mut space);
+        queue.push_back(3).unwrap();
+        queue.push_front(2).unwrap();
+        queue.push_back(4).unwrap();
+        queue.push_front(1).unwrap();
+        assert!(queue.is_full());
+        assert_eq!(queue.push_front(0).unwrap_err().into_element(), 0);
+        assert_eq!(queue.as_slices(), (&[1, 2][..], &[3, 4][..]));
+        assert_eq!(queue.make_contiguous(), &[1, 2, 3, 4]);
+        assert_eq!(queue.pop_back(), Some(4));
+        assert_eq!(queue.pop_front(), Some(1));
+        assert_eq!(queue.back(), Some(&3));
+        assert_eq!(format!("{:?}", queue), "[2, 3]");
+    }
+
+    #[test]
+    fn test_make_contiguous_unwrapped() {
+        let mut space = alloc_stack!([u8; 8]);
+        let mut queue = FixedDeque::new(&mut space);
+        for i in 0..5 {
+            queue.push_back(i).unwrap();
+        }
+        queue.pop_front();
+        queue.pop_front();
+        // Already in one block, so nothing is moved
+        let front = queue.front().unwrap() as *const u8;
+        let items = queue.make_contiguous();
+        assert_eq!(items, &[2, 3, 4]);
+        assert_eq!(items.as_ptr(), front);
+    }
+
+    #[test]
+    fn test_make_contiguous_wrapped() {
+        let drops = Cell::new(0);
+        {
+            let mut space = alloc_stack!(uninit [Droppable; 4]);
+            let mut queue = FixedDeque::from_uninit(&mut space);
+            for i in 0..3 {
+                queue.push_back(Droppable(i, &drops)).unwrap();
+            }
+            drop(queue.pop_front());
+            drop(queue.pop_front());
+            for i in 3..6 {
+                queue.push_back(Droppable(i, &drops)).unwrap();
+            }
+            assert_eq!(queue.as_slices().1.len(), 2);
+            let ids: Vec<u8> = queue.make_contiguous().iter().map(|x| x.0).collect();
+            assert_eq!(ids, &[2, 3, 4, 5]);
+            assert!(queue.as_slices().1.is_empty());
+            assert_eq!(queue.back().map(|x| x.0), Some(5));
+            assert_eq!(drops.get(), 2);
+        }
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    fn test_as_mut_slices_wrapped() {
+        let mut space = alloc_stack!([u8; 4]);
+        let mut queue = FixedDeque::new(&mut space);
+        queue.push_back(1).unwrap();
+        queue.push_back(2).unwrap();
+        queue.push_front(0).unwrap();
+        {
+            let (front, back) = queue.as_mut_slices();
+            assert_eq!((&*front, &*back), (&[0][..], &[1, 2][..]));
+            front[0] = 10;
+            back[1] = 12;
+        }
+        let items: Vec<u8> = queue.iter().cloned().collect();
+        assert_eq!(items, &[10, 1, 12]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_out_of_bounds() {
+        let mut space = alloc_stack!([u8; 4]);
+        let mut queue = FixedDeque::new(&mut space);
+        queue.push_back(1).unwrap();
+        queue.push_front(0).unwrap();
+        // In the buffer, but past the last element
+        let _ = queue[2];
+    }
+
+    #[test]
+    fn test_zero_capacity() {
+        let mut queue = FixedDeque::from_storage([0u8; 0]);
+        assert!(queue.push_back(1).is_err());
+        assert!(queue.push_front(1).is_err());
+        assert_eq!(queue.pop_front(), None);
+        assert!(queue.make_contiguous().is_empty());
+    }
+
+    #[test]
+    fn test_drop_wrapped() {
+        let drops = Cell::new(0);
+        {
+            let mut space = alloc_stack!(uninit [Droppable; 3]);
+            let mut queue = FixedDeque::from_uninit(&mut space);
+            queue.push_back(Droppable(1, &drops)).unwrap();
+            queue.push_front(Droppable(0, &drops)).unwrap();
+            queue.push_back(Droppable(2, &drops)).unwrap();
+            assert_eq!(queue.make_contiguous()[0].0, 0);
+            drop(queue.pop_front());
+            assert_eq!(drops.get(), 1);
+        }
+        assert_eq!(drops.get(), 3);
+    }
+}
@@ -0,0 +1,361 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015-2016 Nick Stevens <nick@bitcurry.com>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Draining and splicing iterators for `FixedVec`.
+
+use core::marker::PhantomData;
+use core::ptr;
+use core::slice;
+
+/// A draining iterator for `FixedVec`.
+///
+/// This struct is created by `FixedVec::drain`. Elements in the drained range
+/// are moved out as they are yielded. When the iterator is dropped, any
+/// elements that were not yielded are dropped, and the elements after the
+/// range are shifted down to close the gap.
+pub struct Drain<'b, T: 'b> {
+    /// Start of the vector's memory.
+    ptr: *mut T,
+    /// The vector's length. Holds the start of the drained range until the
+    /// iterator is dropped.
+    len: &'b mut usize,
+    /// Index of the next element to yield from the front.
+    head: usize,
+    /// One past the index of the next element to yield from the back.
+    end: usize,
+    /// Index of the first element after the drained range.
+    tail_start: usize,
+    /// Number of elements after the drained range.
+    tail_len: usize,
+    marker: PhantomData<&'b mut T>,
+}
+
+impl<'b, T> Drain<'b, T> {
+    /// Create a draining iterator over `start..end` of the memory at `ptr`,
+    /// which holds `*len` live elements.
+    ///
+    /// The caller must ensure that `start <= end <= *len`, and that the
+    /// memory stays borrowed for `'b`.
+    pub(crate) unsafe fn new(ptr: *mut T, len: &'b mut usize, start: usize, end: usize) -> Self {
+        let tail_len = *len - end;
+        *len = start;
+        Drain {
+            ptr,
+            len,
+            head: start,
+            end,
+            tail_start: end,
+            tail_len,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the remaining items of this iterator as a slice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 10]);
+    /// let mut vec = FixedVec::new(&mut space);
+    /// vec.push_all(&[1, 2, 3]).unwrap();
+    ///
+    /// let mut drain = vec.drain(..);
+    /// assert_eq!(drain.as_slice(), &[1, 2, 3]);
+    /// drain.next();
+    /// assert_eq!(drain.as_slice(), &[2, 3]);
+    /// # }
+    /// ```
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr.add(self.head), self.end - self.head) }
+    }
+}
+
+unsafe impl<'b, T: Send> Send for Drain<'b, T> {}
+unsafe impl<'b, T: Sync> Sync for Drain<'b, T> {}
+
+impl<'b, T> Iterator for Drain<'b, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.head == self.end {
+            None
+        } else {
+            let item = unsafe { ptr::read(self.ptr.add(self.head)) };
+            self.head += 1;
+            Some(item)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.head;
+        (len, Some(len))
+    }
+}
+
+impl<'b, T> DoubleEndedIterator for Drain<'b, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        if self.head == self.end {
+            None
+        } else {
+            self.end -= 1;
+            Some(unsafe { ptr::read(self.ptr.add(self.end)) })
+        }
+    }
+}
+
+impl<'b, T> ExactSizeIterator for Drain<'b, T> {}
+
+impl<'b, T> Drop for Drain<'b, T> {
+    fn drop(&mut self) {
+        // If dropping an element panics, the tail is leaked and the vector
+        // keeps only the elements before the drained range.
+        for _ in self.by_ref() {}
+
+        let start = *self.len;
+        if self.tail_len > 0 {
+            unsafe {
+                let src = self.ptr.add(self.tail_start);
+                ptr::copy(src, self.ptr.add(start), self.tail_len);
+            }
+        }
+        *self.len = start + self.tail_len;
+    }
+}
+
+/// A splicing iterator for `FixedVec`.
+///
+/// This struct is created by `FixedVec::splice`. It yields the elements of
+/// the replaced range like `Drain`. When it is dropped, the rest of the range
+/// is dropped and the replacement elements are moved into the gap.
+pub struct Splice<'b, T: 'b, I>
+where
+    I: Iterator<Item = T>,
+{
+    drain: Drain<'b, T>,
+    replace_with: I,
+    /// Number of replacement elements that space was checked for.
+    count: usize,
+}
+
+impl<'b, T, I> Splice<'b, T, I>
+where
+    I: Iterator<Item = T>,
+{
+    /// Create a splicing iterator that replaces the range drained by `drain`
+    /// with the first `count` elements of `replace_with`.
+    ///
+    /// The caller must ensure that the vector has room for `count` elements
+    /// in place of the drained range.
+    pub(crate) unsafe fn new(drain: Drain<'b, T>, replace_with: I, count: usize) -> Self {
+        Splice {
+            drain,
+            replace_with,
+            count,
+        }
+    }
+}
+
+impl<'b, T, I> Iterator for Splice<'b, T, I>
+where
+    I: Iterator<Item = T>,
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.drain.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.drain.size_hint()
+    }
+}
+
+impl<'b, T, I> DoubleEndedIterator for Splice<'b, T, I>
+where
+    I: Iterator<Item = T>,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        self.drain.next_back()
+    }
+}
+
+impl<'b, T, I> ExactSizeIterator for Splice<'b, T, I> where I: Iterator<Item = T> {}
+
+impl<'b, T, I> Drop for Splice<'b, T, I>
+where
+    I: Iterator<Item = T>,
+{
+    fn drop(&mut self) {
+        for _ in self.drain.by_ref() {}
+
+        // Move the tail out of the way of the replacement elements. If the
+        // replacement yields fewer elements than it reported, or panics,
+        // dropping `drain` closes whatever gap is left.
+        let drain = &mut self.drain;
+        let start = *drain.len;
+        let new_tail = start + self.count;
+        if drain.tail_len > 0 && new_tail != drain.tail_start {
+            unsafe {
+                let src = drain.ptr.add(drain.tail_start);
+                ptr::copy(src, drain.ptr.add(new_tail), drain.tail_len);
+            }
+        }
+        drain.tail_start = new_tail;
+
+        for item in self.replace_with.by_ref().take(self.count) {
+            unsafe { ptr::write(drain.ptr.add(*drain.len), item) };
+            *drain.len += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::test_util::Droppable;
+    use super::super::FixedVec;
+    use std::cell::Cell;
+    use std::mem::MaybeUninit;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn test_drain_partial() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3, 4, 5, 6]).unwrap();
+        {
+            let mut drain = vec.drain(1..5);
+            assert_eq!(drain.len(), 4);
+            assert_eq!(drain.next(), Some(2));
+            assert_eq!(drain.next_back(), Some(5));
+            assert_eq!(drain.len(), 2);
+        }
+        assert_eq!(vec.as_slice(), &[1, 6]);
+    }
+
+    #[test]
+    fn test_drain_forgotten() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3, 4]).unwrap();
+        ::core::mem::forget(vec.drain(2..3));
+        assert_eq!(vec.as_slice(), &[1, 2]);
+    }
+
+    #[test]
+    fn test_drain_drops_unyielded() {
+        let drops = Cell::new(0);
+        let mut space: [MaybeUninit<Droppable>; 6] = unsafe { MaybeUninit::uninit().assume_init() };
+        let mut vec = FixedVec::from_uninit(&mut space);
+        for i in 0..6 {
+            vec.push(Droppable(i, &drops)).unwrap();
+        }
+        {
+            let mut drain = vec.drain(..4);
+            assert_eq!(drain.next().map(|x| x.0), Some(0));
+            assert_eq!(drops.get(), 1);
+        }
+        assert_eq!(drops.get(), 4);
+        let rest: Vec<u8> = vec.iter().map(|x| x.0).collect();
+        assert_eq!(rest, &[4, 5]);
+        drop(vec);
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_drain_bad_range() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3]).unwrap();
+        vec.drain(2..4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_drain_range_overflow() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3]).unwrap();
+        vec.drain(..=usize::MAX);
+    }
+
+    #[test]
+    fn test_splice() {
+        let mut space = alloc_stack!([u8; 6]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3, 4]).unwrap();
+
+        // Grow the middle
+        let removed: Vec<u8> = vec.splice(1..2, vec![7, 8, 9]).unwrap().collect();
+        assert_eq!(removed, &[2]);
+        assert_eq!(vec.as_slice(), &[1, 7, 8, 9, 3, 4]);
+
+        // Shrink the middle, without consuming the iterator
+        vec.splice(1..4, Some(5)).unwrap();
+        assert_eq!(vec.as_slice(), &[1, 5, 3, 4]);
+
+        // Too many replacements are rejected without modifying the vector
+        let rest = vec.splice(..1, 0..4).err().unwrap().into_element();
+        assert_eq!(rest, 0..4);
+        assert_eq!(vec.as_slice(), &[1, 5, 3, 4]);
+    }
+
+    #[test]
+    fn test_splice_short_iterator() {
+        // An iterator that reports more elements than it yields
+        struct Liar(u8);
+
+        impl Iterator for Liar {
+            type Item = u8;
+
+            fn next(&mut self) -> Option<u8> {
+                if self.0 < 1 {
+                    self.0 += 1;
+                    Some(9)
+                } else {
+                    None
+                }
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (3, Some(3))
+            }
+        }
+
+        impl ExactSizeIterator for Liar {}
+
+        let mut space = alloc_stack!([u8; 6]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3]).unwrap();
+        vec.splice(1..2, Liar(0)).unwrap();
+        assert_eq!(vec.as_slice(), &[1, 9, 3]);
+    }
+}
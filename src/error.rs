@@ -0,0 +1,170 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015-2016 Nick Stevens <nick@bitcurry.com>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Error types for `FixedVec`.
+
+use core::fmt;
+
+/// The result of a fallible `FixedVec` operation that does not hand a value
+/// back on failure.
+pub type Result<T> = core::result::Result<T, ErrorKind>;
+
+/// The kinds of errors that `FixedVec` operations can report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// There is not enough capacity left for the operation.
+    NoSpace,
+    /// An index or length was outside the valid range.
+    IndexOutOfBounds,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ErrorKind::NoSpace => f.write_str("insufficient capacity"),
+            ErrorKind::IndexOutOfBounds => f.write_str("index out of bounds"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for ErrorKind {}
+
+/// Error returned when an element could not be added because the vector is
+/// full.
+///
+/// The rejected element is handed back, so that types which are expensive or
+/// impossible to reconstruct are not lost. For operations on slices, the
+/// error instead carries the number of elements that would have fit.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate fixedvec;
+/// # use fixedvec::{ErrorKind, FixedVec};
+/// # fn main() {
+/// let mut space = alloc_stack!([u8; 1]);
+/// let mut vec = FixedVec::new(&mut space);
+/// vec.push(1).unwrap();
+///
+/// let err = vec.push(2).unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::NoSpace);
+/// assert_eq!(err.into_element(), 2);
+/// # }
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CapacityError<T = ()> {
+    element: T,
+}
+
+impl<T> CapacityError<T> {
+    /// Creates a new `CapacityError` carrying `element`.
+    pub const fn new(element: T) -> Self {
+        CapacityError { element }
+    }
+
+    /// Returns a reference to the rejected element.
+    pub fn element(&self) -> &T {
+        &self.element
+    }
+
+    /// Extracts the rejected element.
+    pub fn into_element(self) -> T {
+        self.element
+    }
+
+    /// Returns the kind of this error, which is always `ErrorKind::NoSpace`.
+    pub fn kind(&self) -> ErrorKind {
+        ErrorKind::NoSpace
+    }
+
+    /// Drops the rejected element, leaving an error without a payload.
+    pub fn simplify(self) -> CapacityError {
+        CapacityError::new(())
+    }
+}
+
+impl<T> From<CapacityError<T>> for ErrorKind {
+    fn from(err: CapacityError<T>) -> ErrorKind {
+        err.kind()
+    }
+}
+
+impl<T> fmt::Display for CapacityError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.kind(), f)
+    }
+}
+
+// Not derived, so that `unwrap` works on results carrying any element type.
+impl<T> fmt::Debug for CapacityError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CapacityError: {}", self.kind())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> ::std::error::Error for CapacityError<T> {}
+
+#[cfg(test)]
+mod test {
+    use super::super::{ErrorKind, FixedVec, Result};
+    use std::prelude::v1::*;
+
+    fn fill(vec: &mut FixedVec<u8>, count: u8) -> Result<()> {
+        for i in 0..count {
+            vec.push(i)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_question_mark() {
+        let mut space = alloc_stack!([u8; 2]);
+        let mut vec = FixedVec::new(&mut space);
+        assert_eq!(fill(&mut vec, 3), Err(ErrorKind::NoSpace));
+        assert_eq!(vec.as_slice(), &[0, 1]);
+    }
+
+    #[test]
+    fn test_display() {
+        let mut space = alloc_stack!([u8; 1]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push(0).unwrap();
+        let err = vec.push(1).unwrap_err();
+        assert_eq!(err.to_string(), "insufficient capacity");
+        assert_eq!(format!("{:?}", err), "CapacityError: insufficient capacity");
+        assert_eq!(
+            ErrorKind::IndexOutOfBounds.to_string(),
+            "index out of bounds"
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_std_error() {
+        use std::error::Error;
+
+        let err: Box<dyn Error> = Box::new(super::CapacityError::new(5u8));
+        assert_eq!(err.to_string(), "insufficient capacity");
+    }
+}
@@ -0,0 +1,197 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015-2016 Nick Stevens <nick@bitcurry.com>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Extracting iterator for `FixedVec`.
+
+use core::marker::PhantomData;
+use core::ptr;
+
+/// An iterator that removes the elements of a `FixedVec` that match a
+/// predicate.
+///
+/// This struct is created by `FixedVec::extract_if`. Matching elements are
+/// moved out as they are yielded, and the remaining elements are compacted
+/// towards the front. When the iterator is dropped, any elements that were
+/// not yet examined are kept.
+pub struct ExtractIf<'b, T: 'b, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    /// Start of the vector's memory.
+    ptr: *mut T,
+    /// The vector's length. Holds zero until the iterator is dropped.
+    len: &'b mut usize,
+    /// Index of the next element to examine.
+    head: usize,
+    /// Index that the next kept element is moved to.
+    tail: usize,
+    /// One past the last index to examine.
+    end: usize,
+    /// The vector's length before extraction started.
+    old_len: usize,
+    pred: F,
+    marker: PhantomData<&'b mut T>,
+}
+
+impl<'b, T, F> ExtractIf<'b, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    /// Create an iterator that extracts matching elements from `start..end`
+    /// of the memory at `ptr`, which holds `*len` live elements.
+    ///
+    /// The caller must ensure that `start <= end <= *len`, and that the
+    /// memory stays borrowed for `'b`.
+    pub(crate) unsafe fn new(
+        ptr: *mut T,
+        len: &'b mut usize,
+        start: usize,
+        end: usize,
+        pred: F,
+    ) -> Self {
+        // Elements are leaked rather than double-dropped if the iterator is
+        // forgotten.
+        let old_len = *len;
+        *len = 0;
+        ExtractIf {
+            ptr,
+            len,
+            head: start,
+            tail: start,
+            end,
+            old_len,
+            pred,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'b, T, F> Iterator for ExtractIf<'b, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while self.head < self.end {
+            unsafe {
+                let cur = self.ptr.add(self.head);
+                // If `pred` panics, `head` still refers to this element, and
+                // dropping the iterator keeps it.
+                let extract = (self.pred)(&mut *cur);
+                self.head += 1;
+                if extract {
+                    return Some(ptr::read(cur));
+                }
+                if self.tail != self.head - 1 {
+                    ptr::copy(cur, self.ptr.add(self.tail), 1);
+                }
+                self.tail += 1;
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.end - self.head))
+    }
+}
+
+impl<'b, T, F> Drop for ExtractIf<'b, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    fn drop(&mut self) {
+        let rest = self.old_len - self.head;
+        if rest > 0 && self.tail != self.head {
+            unsafe {
+                let src = self.ptr.add(self.head);
+                ptr::copy(src, self.ptr.add(self.tail), rest);
+            }
+        }
+        *self.len = self.tail + rest;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::test_util::Droppable;
+    use super::super::FixedVec;
+    use std::cell::Cell;
+    use std::mem::MaybeUninit;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn test_extract_if_range() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+        let evens: Vec<u8> = vec.extract_if(1..7, |x| *x % 2 == 0).collect();
+        assert_eq!(evens, &[2, 4, 6]);
+        assert_eq!(vec.as_slice(), &[1, 3, 5, 7, 8]);
+    }
+
+    #[test]
+    fn test_extract_if_partial() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3, 4, 5, 6]).unwrap();
+        assert_eq!(vec.extract_if(.., |x| *x % 2 == 0).next(), Some(2));
+        assert_eq!(vec.as_slice(), &[1, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_extract_if_forgotten() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3]).unwrap();
+        ::core::mem::forget(vec.extract_if(.., |_| true));
+        assert!(vec.is_empty());
+    }
+
+    #[test]
+    fn test_extract_if_panic() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let drops = Cell::new(0);
+        let mut space: [MaybeUninit<Droppable>; 6] = unsafe { MaybeUninit::uninit().assume_init() };
+        let mut vec = FixedVec::from_uninit(&mut space);
+        for i in 0..6 {
+            vec.push(Droppable(i, &drops)).unwrap();
+        }
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            for _ in vec.extract_if(.., |x| {
+                if x.0 == 3 {
+                    std::panic!("boom");
+                }
+                x.0 % 2 == 0
+            }) {}
+        }));
+        assert!(result.is_err());
+        assert_eq!(drops.get(), 2);
+        let rest: Vec<u8> = vec.iter().map(|x| x.0).collect();
+        assert_eq!(rest, &[1, 3, 4, 5]);
+        drop(vec);
+        assert_eq!(drops.get(), 6);
+    }
+}
@@ -0,0 +1,965 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015-2016 Nick Stevens <nick@bitcurry.com>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Open-addressing hash map over borrowed memory.
+
+use core::borrow::Borrow;
+use core::fmt;
+use core::hash::{BuildHasher, Hash, Hasher};
+use core::iter::FusedIterator;
+use core::mem;
+use core::mem::MaybeUninit;
+use core::slice;
+
+use super::{ErrorKind, FnvBuildHasher, Result};
+
+/// A single slot of a `FixedHashMap`.
+///
+/// The backing memory of a map is a slice of uninitialized slots, which can
+/// be allocated with `alloc_stack!(uninit [HashSlot<K, V>; N])`.
+pub struct HashSlot<K, V> {
+    /// Distance from the home slot plus one, or zero if the slot is empty.
+    dist: usize,
+    /// Low bits of the hash, to skip most key comparisons.
+    tag: u32,
+    entry: MaybeUninit<(K, V)>,
+}
+
+impl<K, V> HashSlot<K, V> {
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.dist == 0
+    }
+
+    /// Returns the entry of the slot.
+    ///
+    /// # Safety
+    ///
+    /// The slot must not be empty.
+    #[inline]
+    unsafe fn entry(&self) -> &(K, V) {
+        &*self.entry.as_ptr()
+    }
+
+    /// Returns the entry of the slot.
+    ///
+    /// # Safety
+    ///
+    /// The slot must not be empty.
+    #[inline]
+    unsafe fn entry_mut(&mut self) -> &mut (K, V) {
+        &mut *self.entry.as_mut_ptr()
+    }
+
+    /// Empties the slot, returning its entry.
+    ///
+    /// # Safety
+    ///
+    /// The slot must not be empty.
+    #[inline]
+    unsafe fn take(&mut self) -> (K, V) {
+        self.dist = 0;
+        mem::replace(&mut self.entry, MaybeUninit::uninit()).assume_init()
+    }
+}
+
+/// A hash map that stores its entries in a caller-provided slot buffer.
+///
+/// Collisions are resolved with Robin Hood linear probing, and removals use
+/// backward-shift deletion, so no tombstones accumulate. The map can be
+/// filled to capacity, but probe sequences grow quickly past a load factor
+/// of about 0.9, so size the buffer with some headroom.
+///
+/// Iteration visits entries in slot order. With a deterministic hasher, such
+/// as the default `FnvBuildHasher`, the same sequence of operations always
+/// produces the same order.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate fixedvec;
+/// # use fixedvec::{FixedHashMap, HashSlot};
+/// # fn main() {
+/// let mut space = alloc_stack!(uninit [HashSlot<u32, &str>; 16]);
+/// let mut routes = FixedHashMap::new(&mut space);
+/// routes.insert(0x0a00_0001, "eth0").unwrap();
+/// routes.insert(0x0a00_0002, "eth1").unwrap();
+///
+/// assert_eq!(routes.get(&0x0a00_0002), Some(&"eth1"));
+/// assert_eq!(routes.remove(&0x0a00_0001), Some("eth0"));
+/// assert_eq!(routes.len(), 1);
+/// # }
+/// ```
+pub struct FixedHashMap<'a, K: 'a, V: 'a, S = FnvBuildHasher>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    slots: &'a mut [HashSlot<K, V>],
+    len: usize,
+    hash_builder: S,
+}
+
+impl<'a, K, V> FixedHashMap<'a, K, V>
+where
+    K: Eq + Hash,
+{
+    /// Create a new, empty `FixedHashMap` using the slots in `memory` and the
+    /// default FNV hasher.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::{FixedHashMap, HashSlot};
+    /// # fn main() {
+    /// let mut space = alloc_stack!(uninit [HashSlot<u8, u8>; 8]);
+    /// let map = FixedHashMap::new(&mut space);
+    /// assert_eq!(map.capacity(), 8);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn new(memory: &'a mut [MaybeUninit<HashSlot<K, V>>]) -> Self {
+        FixedHashMap::with_hasher(memory, FnvBuildHasher::default())
+    }
+}
+
+impl<'a, K, V, S> FixedHashMap<'a, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Create a new, empty `FixedHashMap` using the slots in `memory` and
+    /// `hash_builder` to hash keys.
+    pub fn with_hasher(memory: &'a mut [MaybeUninit<HashSlot<K, V>>], hash_builder: S) -> Self {
+        for slot in memory.iter_mut() {
+            *slot = MaybeUninit::new(HashSlot {
+                dist: 0,
+                tag: 0,
+                entry: MaybeUninit::uninit(),
+            });
+        }
+        // Every slot was just initialized, and `MaybeUninit<T>` has the same
+        // layout as `T`.
+        let slots = unsafe {
+            &mut *(memory as *mut [MaybeUninit<HashSlot<K, V>>] as *mut [HashSlot<K, V>])
+        };
+        FixedHashMap {
+            slots,
+            len: 0,
+            hash_builder,
+        }
+    }
+
+    /// Returns the number of entries the map can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns the number of entries in the map.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map contains no entries.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the map has no space left for more entries.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len == self.capacity()
+    }
+
+    /// Returns the fraction of slots that are occupied, from `0.0` to `1.0`.
+    ///
+    /// A map without any slots reports `1.0`, as it is full.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::{FixedHashMap, HashSlot};
+    /// # fn main() {
+    /// let mut space = alloc_stack!(uninit [HashSlot<u8, ()>; 8]);
+    /// let mut set = FixedHashMap::new(&mut space);
+    /// for k in 0..6 {
+    ///     set.insert(k, ()).unwrap();
+    /// }
+    /// assert_eq!(set.load_factor(), 0.75);
+    /// # }
+    /// ```
+    pub fn load_factor(&self) -> f32 {
+        if self.slots.is_empty() {
+            1.0
+        } else {
+            self.len as f32 / self.slots.len() as f32
+        }
+    }
+
+    /// Returns a reference to the map's `BuildHasher`.
+    #[inline]
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    /// Removes all entries from the map.
+    pub fn clear(&mut self) {
+        for slot in self.slots.iter_mut() {
+            if !slot.is_empty() {
+                // Empty the slot before dropping, so a panicking destructor
+                // leaks the rest of the entries instead of double-dropping.
+                self.len -= 1;
+                drop(unsafe { slot.take() });
+            }
+        }
+    }
+
+    // `BuildHasher::hash_one` is newer than the minimum supported Rust.
+    #[allow(clippy::manual_hash_one)]
+    fn make_hash<Q>(&self, key: &Q) -> u64
+    where
+        Q: Hash + ?Sized,
+    {
+        let mut hasher = self.hash_builder.build_hasher();
+        key.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Returns the slot where probing for `hash` starts.
+    #[inline]
+    fn home(&self, hash: u64) -> usize {
+        (hash % self.slots.len() as u64) as usize
+    }
+
+    /// Returns the index of the slot holding `key`, which hashes to `hash`.
+    fn find<Q>(&self, hash: u64, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        if self.len == 0 {
+            return None;
+        }
+        let n = self.slots.len();
+        let mut pos = self.home(hash);
+        for dist in 1..=n {
+            let slot = &self.slots[pos];
+            // Robin Hood keeps every entry at least as far from home as the
+            // ones probed before it, so a closer entry ends the search.
+            if slot.dist < dist {
+                return None;
+            }
+            if slot.tag == hash as u32 && unsafe { slot.entry() }.0.borrow() == key {
+                return Some(pos);
+            }
+            pos = if pos + 1 == n { 0 } else { pos + 1 };
+        }
+        None
+    }
+
+    /// Stores an entry whose key is not yet in the map, returning the index
+    /// of the slot it ends up in.
+    ///
+    /// The map must not be full.
+    fn insert_new(&mut self, hash: u64, key: K, value: V) -> usize {
+        debug_assert!(!self.is_full());
+        let n = self.slots.len();
+        let mut pos = self.home(hash);
+        let mut dist = 1;
+        let mut tag = hash as u32;
+        let mut entry = MaybeUninit::new((key, value));
+        let mut placed = None;
+        loop {
+            let slot = &mut self.slots[pos];
+            if slot.is_empty() {
+                slot.dist = dist;
+                slot.tag = tag;
+                slot.entry = entry;
+                self.len += 1;
+                return placed.unwrap_or(pos);
+            }
+            // Take the slot from an entry closer to its home, and carry that
+            // entry on to the next slot instead.
+            if slot.dist < dist {
+                mem::swap(&mut slot.dist, &mut dist);
+                mem::swap(&mut slot.tag, &mut tag);
+                mem::swap(&mut slot.entry, &mut entry);
+                if placed.is_none() {
+                    placed = Some(pos);
+                }
+            }
+            pos = if pos + 1 == n { 0 } else { pos + 1 };
+            dist += 1;
+        }
+    }
+
+    /// Removes the entry at `index`, shifting the entries after it back
+    /// towards their home slots.
+    fn remove_at(&mut self, index: usize) -> (K, V) {
+        let n = self.slots.len();
+        let entry = unsafe { self.slots[index].take() };
+        self.len -= 1;
+        let mut hole = index;
+        loop {
+            let next = if hole + 1 == n { 0 } else { hole + 1 };
+            if self.slots[next].dist <= 1 {
+                break;
+            }
+            let dist = self.slots[next].dist - 1;
+            let tag = self.slots[next].tag;
+            let moved = mem::replace(&mut self.slots[next].entry, MaybeUninit::uninit());
+            self.slots[next].dist = 0;
+            self.slots[hole] = HashSlot {
+                dist,
+                tag,
+                entry: moved,
+            };
+            hole = next;
+        }
+        entry
+    }
+
+    /// Returns `true` if the map contains a value for `key`.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.find(self.make_hash(key), key).is_some()
+    }
+
+    /// Returns a reference to the value for `key`, or `None` if the map does
+    /// not contain it.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.get_key_value(key).map(|(_, v)| v)
+    }
+
+    /// Returns the stored key and a reference to the value for `key`, or
+    /// `None` if the map does not contain it.
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        match self.find(self.make_hash(key), key) {
+            Some(i) => {
+                let (ref k, ref v) = *unsafe { self.slots[i].entry() };
+                Some((k, v))
+            }
+            None => None,
+        }
+    }
+
+    /// Returns a mutable reference to the value for `key`, or `None` if the
+    /// map does not contain it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::{FixedHashMap, HashSlot};
+    /// # fn main() {
+    /// let mut space = alloc_stack!(uninit [HashSlot<u8, u32>; 4]);
+    /// let mut map = FixedHashMap::new(&mut space);
+    /// map.insert(1, 10).unwrap();
+    /// *map.get_mut(&1).unwrap() += 1;
+    /// assert_eq!(map.get(&1), Some(&11));
+    /// # }
+    /// ```
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        match self.find(self.make_hash(key), key) {
+            Some(i) => Some(&mut unsafe { self.slots[i].entry_mut() }.1),
+            None => None,
+        }
+    }
+
+    /// Inserts `value` for `key`, returning the previous value if the map
+    /// already contained `key`. The stored key is left unchanged in that case.
+    ///
+    /// Returns `ErrorKind::NoSpace` if `key` is new and the map is full.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::{ErrorKind, FixedHashMap, HashSlot};
+    /// # fn main() {
+    /// let mut space = alloc_stack!(uninit [HashSlot<u8, u8>; 2]);
+    /// let mut map = FixedHashMap::new(&mut space);
+    /// assert_eq!(map.insert(1, 10), Ok(None));
+    /// assert_eq!(map.insert(2, 20), Ok(None));
+    /// assert_eq!(map.insert(1, 11), Ok(Some(10)));
+    /// assert_eq!(map.insert(3, 30), Err(ErrorKind::NoSpace));
+    /// # }
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Result<Option<V>> {
+        let hash = self.make_hash(&key);
+        if let Some(i) = self.find(hash, &key) {
+            let entry = unsafe { self.slots[i].entry_mut() };
+            return Ok(Some(mem::replace(&mut entry.1, value)));
+        }
+        if self.is_full() {
+            return Err(ErrorKind::NoSpace);
+        }
+        self.insert_new(hash, key, value);
+        Ok(None)
+    }
+
+    /// Removes `key` from the map, returning its value if the map contained
+    /// it.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    /// Removes `key` from the map, returning the stored key and its value if
+    /// the map contained it.
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let hash = self.make_hash(key);
+        self.find(hash, key).map(|i| self.remove_at(i))
+    }
+
+    /// Gets the entry for `key`, for in-place insertion or modification.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::{FixedHashMap, HashSlot};
+    /// # fn main() {
+    /// let mut space = alloc_stack!(uninit [HashSlot<char, u32>; 8]);
+    /// let mut counts = FixedHashMap::new(&mut space);
+    /// for c in "hello".chars() {
+    ///     *counts.entry(c).or_insert(0).unwrap() += 1;
+    /// }
+    /// assert_eq!(counts.get(&'l'), Some(&2));
+    /// assert_eq!(counts.len(), 4);
+    /// # }
+    /// ```
+    pub fn entry(&mut self, key: K) -> HashEntry<'_, 'a, K, V, S> {
+        let hash = self.make_hash(&key);
+        match self.find(hash, &key) {
+            Some(index) => HashEntry::Occupied(HashOccupiedEntry { map: self, index }),
+            None => HashEntry::Vacant(HashVacantEntry {
+                map: self,
+                hash,
+                key,
+            }),
+        }
+    }
+
+    /// Provides an iterator over the entries of the map, in slot order.
+    #[inline]
+    pub fn iter(&self) -> HashMapIter<'_, K, V> {
+        HashMapIter {
+            slots: self.slots.iter(),
+            remaining: self.len,
+        }
+    }
+
+    /// Provides an iterator over the entries of the map, in slot order, with
+    /// mutable references to the values.
+    #[inline]
+    pub fn iter_mut(&mut self) -> HashMapIterMut<'_, K, V> {
+        HashMapIterMut {
+            slots: self.slots.iter_mut(),
+            remaining: self.len,
+        }
+    }
+}
+
+impl<'a, K, V, S> Drop for FixedHashMap<'a, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<'a, K, V, S> fmt::Debug for FixedHashMap<'a, K, V, S>
+where
+    K: Eq + Hash + fmt::Debug,
+    V: fmt::Debug,
+    S: BuildHasher,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, 'b, K, V, S, S2> PartialEq<FixedHashMap<'b, K, V, S2>> for FixedHashMap<'a, K, V, S>
+where
+    K: Eq + Hash,
+    V: PartialEq,
+    S: BuildHasher,
+    S2: BuildHasher,
+{
+    fn eq(&self, other: &FixedHashMap<'b, K, V, S2>) -> bool {
+        self.len() == other.len() && self.iter().all(|(k, v)| other.get(k) == Some(v))
+    }
+}
+
+impl<'a, K, V, S> Eq for FixedHashMap<'a, K, V, S>
+where
+    K: Eq + Hash,
+    V: Eq,
+    S: BuildHasher,
+{
+}
+
+impl<'a, 'b, K, V, S> IntoIterator for &'b FixedHashMap<'a, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    type Item = (&'b K, &'b V);
+    type IntoIter = HashMapIter<'b, K, V>;
+
+    fn into_iter(self) -> HashMapIter<'b, K, V> {
+        self.iter()
+    }
+}
+
+impl<'a, 'b, K, V, S> IntoIterator for &'b mut FixedHashMap<'a, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    type Item = (&'b K, &'b mut V);
+    type IntoIter = HashMapIterMut<'b, K, V>;
+
+    fn into_iter(self) -> HashMapIterMut<'b, K, V> {
+        self.iter_mut()
+    }
+}
+
+/// An iterator over the entries of a `FixedHashMap`, in slot order.
+///
+/// This struct is created by `FixedHashMap::iter`.
+pub struct HashMapIter<'b, K: 'b, V: 'b> {
+    slots: slice::Iter<'b, HashSlot<K, V>>,
+    remaining: usize,
+}
+
+impl<'b, K, V> Clone for HashMapIter<'b, K, V> {
+    fn clone(&self) -> Self {
+        HashMapIter {
+            slots: self.slots.clone(),
+            remaining: self.remaining,
+        }
+    }
+}
+
+impl<'b, K, V> Iterator for HashMapIter<'b, K, V> {
+    type Item = (&'b K, &'b V);
+
+    fn next(&mut self) -> Option<(&'b K, &'b V)> {
+        let slot = self.slots.find(|slot| !slot.is_empty())?;
+        self.remaining -= 1;
+        let (ref k, ref v) = *unsafe { slot.entry() };
+        Some((k, v))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'b, K, V> ExactSizeIterator for HashMapIter<'b, K, V> {}
+
+impl<'b, K, V> FusedIterator for HashMapIter<'b, K, V> {}
+
+/// A mutable iterator over the entries of a `FixedHashMap`, in slot order.
+///
+/// This struct is created by `FixedHashMap::iter_mut`.
+pub struct HashMapIterMut<'b, K: 'b, V: 'b> {
+    slots: slice::IterMut<'b, HashSlot<K, V>>,
+    remaining: usize,
+}
+
+impl<'b, K, V> Iterator for HashMapIterMut<'b, K, V> {
+    type Item = (&'b K, &'b mut V);
+
+    fn next(&mut self) -> Option<(&'b K, &'b mut V)> {
+        let slot = self.slots.find(|slot| !slot.is_empty())?;
+        self.remaining -= 1;
+        let (ref k, ref mut v) = *unsafe { slot.entry_mut() };
+        Some((k, v))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'b, K, V> ExactSizeIterator for HashMapIterMut<'b, K, V> {}
+
+impl<'b, K, V> FusedIterator for HashMapIterMut<'b, K, V> {}
+
+/// A view into a single entry of a `FixedHashMap`, which may be vacant or
+/// occupied.
+///
+/// This enum is created by `FixedHashMap::entry`.
+pub enum HashEntry<'b, 'a: 'b, K: 'a + Eq + Hash, V: 'a, S: 'b>
+where
+    S: BuildHasher,
+{
+    /// An entry whose key is in the map.
+    Occupied(HashOccupiedEntry<'b, 'a, K, V, S>),
+    /// An entry whose key is not in the map.
+    Vacant(HashVacantEntry<'b, 'a, K, V, S>),
+}
+
+impl<'b, 'a, K, V, S> HashEntry<'b, 'a, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Returns the key of this entry.
+    pub fn key(&self) -> &K {
+        match *self {
+            HashEntry::Occupied(ref e) => e.key(),
+            HashEntry::Vacant(ref e) => e.key(),
+        }
+    }
+
+    /// Inserts `default` if the entry is vacant, then returns a mutable
+    /// reference to the value.
+    ///
+    /// Returns `ErrorKind::NoSpace` if the entry is vacant and the map is
+    /// full.
+    pub fn or_insert(self, default: V) -> Result<&'b mut V> {
+        match self {
+            HashEntry::Occupied(e) => Ok(e.into_mut()),
+            HashEntry::Vacant(e) => e.insert(default),
+        }
+    }
+
+    /// Inserts the result of `default` if the entry is vacant, then returns a
+    /// mutable reference to the value.
+    ///
+    /// Returns `ErrorKind::NoSpace`, without calling `default`, if the entry
+    /// is vacant and the map is full.
+    pub fn or_insert_with<F>(self, default: F) -> Result<&'b mut V>
+    where
+        F: FnOnce() -> V,
+    {
+        match self {
+            HashEntry::Occupied(e) => Ok(e.into_mut()),
+            HashEntry::Vacant(e) => {
+                if e.map.is_full() {
+                    return Err(ErrorKind::NoSpace);
+                }
+                e.insert(default())
+            }
+        }
+    }
+
+    /// Calls `f` on the value if the entry is occupied.
+    pub fn and_modify<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&mut V),
+    {
+        if let HashEntry::Occupied(ref mut e) = self {
+            f(e.get_mut());
+        }
+        self
+    }
+}
+
+impl<'b, 'a, K, V, S> HashEntry<'b, 'a, K, V, S>
+where
+    K: Eq + Hash,
+    V: Default,
+    S: BuildHasher,
+{
+    /// Inserts the default value if the entry is vacant, then returns a
+    /// mutable reference to the value.
+    ///
+    /// Returns `ErrorKind::NoSpace` if the entry is vacant and the map is
+    /// full.
+    pub fn or_default(self) -> Result<&'b mut V> {
+        self.or_insert_with(V::default)
+    }
+}
+
+/// A view into an occupied entry of a `FixedHashMap`.
+pub struct HashOccupiedEntry<'b, 'a: 'b, K: 'a + Eq + Hash, V: 'a, S: 'b>
+where
+    S: BuildHasher,
+{
+    map: &'b mut FixedHashMap<'a, K, V, S>,
+    index: usize,
+}
+
+impl<'b, 'a, K, V, S> HashOccupiedEntry<'b, 'a, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Returns the key of this entry.
+    pub fn key(&self) -> &K {
+        &unsafe { self.map.slots[self.index].entry() }.0
+    }
+
+    /// Returns a reference to the value of this entry.
+    pub fn get(&self) -> &V {
+        &unsafe { self.map.slots[self.index].entry() }.1
+    }
+
+    /// Returns a mutable reference to the value of this entry.
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut unsafe { self.map.slots[self.index].entry_mut() }.1
+    }
+
+    /// Converts the entry into a mutable reference to its value, with the
+    /// lifetime of the map borrow.
+    pub fn into_mut(self) -> &'b mut V {
+        &mut unsafe { self.map.slots[self.index].entry_mut() }.1
+    }
+
+    /// Replaces the value of this entry, returning the old value.
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes this entry from the map, returning its value.
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    /// Removes this entry from the map, returning its key and value.
+    pub fn remove_entry(self) -> (K, V) {
+        self.map.remove_at(self.index)
+    }
+}
+
+/// A view into a vacant entry of a `FixedHashMap`.
+pub struct HashVacantEntry<'b, 'a: 'b, K: 'a + Eq + Hash, V: 'a, S: 'b>
+where
+    S: BuildHasher,
+{
+    map: &'b mut FixedHashMap<'a, K, V, S>,
+    hash: u64,
+    key: K,
+}
+
+impl<'b, 'a, K, V, S> HashVacantEntry<'b, 'a, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Returns the key that would be used for insertion.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Takes ownership of the key.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Inserts `value` for this entry's key, returning a mutable reference to
+    /// it.
+    ///
+    /// Returns `ErrorKind::NoSpace` if the map is full.
+    pub fn insert(self, value: V) -> Result<&'b mut V> {
+        if self.map.is_full() {
+            return Err(ErrorKind::NoSpace);
+        }
+        let index = self.map.insert_new(self.hash, self.key, value);
+        Ok(&mut unsafe { self.map.slots[index].entry_mut() }.1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::test_util::Droppable;
+    use super::{ErrorKind, FixedHashMap, HashEntry, HashSlot};
+    use std::cell::Cell;
+    use std::hash::{BuildHasherDefault, Hasher};
+    use std::prelude::v1::*;
+
+    /// Hashes every integer to itself, so tests control the home slots.
+    #[derive(Default)]
+    struct IdentityHasher(u64);
+
+    impl Hasher for IdentityHasher {
+        fn finish(&self) -> u64 {
+            self.0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for &byte in bytes {
+                self.0 = (self.0 << 8) | u64::from(byte);
+            }
+        }
+
+        fn write_u32(&mut self, n: u32) {
+            self.0 = u64::from(n);
+        }
+    }
+
+    type Identity = BuildHasherDefault<IdentityHasher>;
+
+    /// Checks that every entry is reachable and that no entry could be moved
+    /// closer to home by the Robin Hood rule.
+    fn check_invariants(map: &FixedHashMap<u32, u32, Identity>) {
+        let n = map.slots.len();
+        for (i, slot) in map.slots.iter().enumerate() {
+            if slot.is_empty() {
+                continue;
+            }
+            let key = unsafe { slot.entry() }.0;
+            assert_eq!((key as usize % n + slot.dist - 1) % n, i);
+            let next = &map.slots[(i + 1) % n];
+            assert!(next.dist <= slot.dist + 1);
+        }
+        assert_eq!(map.iter().count(), map.len());
+    }
+
+    #[test]
+    fn test_collisions_and_wrapping() {
+        let mut space = alloc_stack!(uninit [HashSlot<u32, u32>; 8]);
+        let mut map = FixedHashMap::with_hasher(&mut space, Identity::default());
+        // All of these start probing at slot 6, so they wrap around
+        for &k in &[6, 14, 22, 7, 30, 5] {
+            map.insert(k, k * 10).unwrap();
+            check_invariants(&map);
+        }
+        for &k in &[6, 14, 22, 7, 30, 5] {
+            assert_eq!(map.get(&k), Some(&(k * 10)));
+        }
+        assert_eq!(map.get(&38), None);
+        assert_eq!(map.remove(&14), Some(140));
+        check_invariants(&map);
+        assert_eq!(map.remove(&6), Some(60));
+        check_invariants(&map);
+        for &k in &[22, 7, 30, 5] {
+            assert_eq!(map.get(&k), Some(&(k * 10)));
+        }
+        assert_eq!(map.len(), 4);
+    }
+
+    #[test]
+    fn test_fill_to_capacity() {
+        let mut space = alloc_stack!(uninit [HashSlot<u32, u32>; 5]);
+        let mut map = FixedHashMap::with_hasher(&mut space, Identity::default());
+        for k in 0..5 {
+            map.insert(k * 5, k).unwrap();
+        }
+        check_invariants(&map);
+        assert!(map.is_full());
+        assert_eq!(map.load_factor(), 1.0);
+        assert_eq!(map.insert(1, 0), Err(ErrorKind::NoSpace));
+        assert_eq!(map.insert(10, 9), Ok(Some(2)));
+        // A miss in a full table must still terminate
+        assert_eq!(map.get(&1), None);
+        for k in 0..5 {
+            assert!(map.contains_key(&(k * 5)));
+        }
+    }
+
+    #[test]
+    fn test_zero_capacity() {
+        let mut space = alloc_stack!(uninit [HashSlot<u32, u32>; 0]);
+        let mut map = FixedHashMap::new(&mut space);
+        assert_eq!(map.get(&1), None);
+        assert_eq!(map.insert(1, 1), Err(ErrorKind::NoSpace));
+        assert_eq!(map.entry(1).or_insert(1).err(), Some(ErrorKind::NoSpace));
+        assert_eq!(map.remove(&1), None);
+    }
+
+    #[test]
+    fn test_deterministic_order() {
+        let order = || {
+            let mut space = alloc_stack!(uninit [HashSlot<String, usize>; 16]);
+            let mut map = FixedHashMap::new(&mut space);
+            for (i, name) in ["eth0", "eth1", "wlan0", "lo", "tun0"].iter().enumerate() {
+                map.insert(name.to_string(), i).unwrap();
+            }
+            map.remove("lo");
+            map.iter().map(|(k, _)| k.clone()).collect::<Vec<_>>()
+        };
+        let first = order();
+        assert_eq!(first.len(), 4);
+        assert_eq!(first, order());
+    }
+
+    #[test]
+    fn test_entry_and_drop() {
+        let drops = Cell::new(0);
+        {
+            let mut space = alloc_stack!(uninit [HashSlot<u8, Droppable>; 4]);
+            let mut map = FixedHashMap::new(&mut space);
+            for i in 0..4 {
+                map.entry(i)
+                    .or_insert_with(|| Droppable(i * 10, &drops))
+                    .unwrap();
+            }
+            let mut built = false;
+            let res = map.entry(9).or_insert_with(|| {
+                built = true;
+                Droppable(90, &drops)
+            });
+            assert_eq!(res.err(), Some(ErrorKind::NoSpace));
+            assert!(!built);
+
+            match map.entry(2) {
+                HashEntry::Occupied(e) => assert_eq!(e.remove().0, 20),
+                HashEntry::Vacant(_) => unreachable!(),
+            }
+            assert_eq!(drops.get(), 1);
+            map.insert(1, Droppable(11, &drops)).unwrap();
+            assert_eq!(drops.get(), 2);
+            for (_, v) in map.iter_mut() {
+                v.0 += 1;
+            }
+            assert_eq!(map.get(&1).map(|d| d.0), Some(12));
+        }
+        assert_eq!(drops.get(), 5);
+    }
+}
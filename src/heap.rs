@@ -0,0 +1,573 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015-2016 Nick Stevens <nick@bitcurry.com>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Heapless priority queue.
+
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ops;
+
+use super::{CapacityError, FixedVec, Iter, Storage};
+
+/// A priority queue implemented as a binary max-heap over a `FixedVec`.
+///
+/// `pop` and `peek` return the greatest element. For a min-heap, wrap the
+/// elements in `core::cmp::Reverse`.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate fixedvec;
+/// # use fixedvec::FixedBinaryHeap;
+/// use std::cmp::Reverse;
+///
+/// # fn main() {
+/// // Timers, earliest deadline first
+/// let mut space = alloc_stack!(uninit [Reverse<u32>; 8]);
+/// let mut timers = FixedBinaryHeap::from_uninit(&mut space);
+/// timers.push(Reverse(30)).unwrap();
+/// timers.push(Reverse(10)).unwrap();
+/// timers.push(Reverse(20)).unwrap();
+///
+/// assert_eq!(timers.pop(), Some(Reverse(10)));
+/// assert_eq!(timers.peek(), Some(&Reverse(20)));
+/// # }
+/// ```
+pub struct FixedBinaryHeap<'a, T, S = &'a mut [T]>
+where
+    T: Ord,
+    S: Storage<T>,
+{
+    data: FixedVec<'a, T, S>,
+}
+
+impl<'a, T> FixedBinaryHeap<'a, T>
+where
+    T: 'a + Ord + Copy,
+{
+    /// Create a new, empty `FixedBinaryHeap` from the provided slice.
+    ///
+    /// Only `Copy` types can be stored in a plain slice. Use `from_uninit`
+    /// for other types.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedBinaryHeap;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut heap = FixedBinaryHeap::new(&mut space);
+    /// heap.push(1).unwrap();
+    /// heap.push(3).unwrap();
+    /// assert_eq!(heap.peek(), Some(&3));
+    /// # }
+    /// ```
+    pub fn new(memory: &'a mut [T]) -> Self {
+        FixedBinaryHeap::from_vec(FixedVec::new(memory))
+    }
+}
+
+impl<'a, T> FixedBinaryHeap<'a, T, &'a mut [MaybeUninit<T>]>
+where
+    T: Ord,
+{
+    /// Create a new, empty `FixedBinaryHeap` from a slice of
+    /// possibly-uninitialized memory.
+    pub fn from_uninit(memory: &'a mut [MaybeUninit<T>]) -> Self {
+        FixedBinaryHeap::from_vec(FixedVec::from_uninit(memory))
+    }
+}
+
+impl<'a, T, S> FixedBinaryHeap<'a, T, S>
+where
+    T: Ord,
+    S: Storage<T>,
+{
+    /// Create a `FixedBinaryHeap` that takes over the elements and storage of
+    /// a `FixedVec`, rearranging the elements into a heap in O(N) time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::{FixedBinaryHeap, FixedVec};
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 8]);
+    /// let mut vec = FixedVec::new(&mut space);
+    /// vec.push_all(&[3, 1, 4, 1, 5]).unwrap();
+    ///
+    /// let mut heap = FixedBinaryHeap::from_vec(vec);
+    /// assert_eq!(heap.pop(), Some(5));
+    /// assert_eq!(heap.pop(), Some(4));
+    /// # }
+    /// ```
+    pub fn from_vec(vec: FixedVec<'a, T, S>) -> Self {
+        let mut heap = FixedBinaryHeap { data: vec };
+        heap.rebuild();
+        heap
+    }
+
+    /// Consumes the heap, returning its elements in an arbitrary order.
+    #[inline]
+    pub fn into_vec(self) -> FixedVec<'a, T, S> {
+        self.data
+    }
+
+    /// Consumes the heap, returning its elements sorted in ascending order.
+    ///
+    /// The elements are sorted in place with heapsort.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedBinaryHeap;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 8]);
+    /// let mut heap = FixedBinaryHeap::new(&mut space);
+    /// for &x in &[3, 1, 4, 1, 5] {
+    ///     heap.push(x).unwrap();
+    /// }
+    /// assert_eq!(heap.into_sorted().as_slice(), &[1, 1, 3, 4, 5]);
+    /// # }
+    /// ```
+    pub fn into_sorted(mut self) -> FixedVec<'a, T, S> {
+        let mut end = self.data.len();
+        while end > 1 {
+            end -= 1;
+            self.data.as_mut_slice().swap(0, end);
+            self.sift_down_range(0, end);
+        }
+        self.data
+    }
+
+    /// Returns the capacity of the heap.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// Returns the number of elements in the heap.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the heap contains no elements.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns `true` if the heap has no space left for more elements.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.data.is_full()
+    }
+
+    /// Returns the elements of the heap, in an arbitrary order.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        self.data.as_slice()
+    }
+
+    /// Provides an iterator over the elements of the heap, in an arbitrary
+    /// order.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.data.iter()
+    }
+
+    /// Returns the greatest element in the heap, or `None` if it is empty.
+    #[inline]
+    pub fn peek(&self) -> Option<&T> {
+        self.data.get(0)
+    }
+
+    /// Returns a guard that gives mutable access to the greatest element in
+    /// the heap, or `None` if it is empty.
+    ///
+    /// If the element is modified, the heap is restored when the guard is
+    /// dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedBinaryHeap;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut heap = FixedBinaryHeap::new(&mut space);
+    /// heap.push(5).unwrap();
+    /// heap.push(3).unwrap();
+    /// {
+    ///     let mut top = heap.peek_mut().unwrap();
+    ///     *top = 1;
+    /// }
+    /// assert_eq!(heap.peek(), Some(&3));
+    /// # }
+    /// ```
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, 'a, T, S>> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(PeekMut {
+                heap: self,
+                original_len: None,
+            })
+        }
+    }
+
+    /// Pushes an element onto the heap, in O(log N) time.
+    ///
+    /// If the heap is full, `value` is handed back in the error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedBinaryHeap;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 2]);
+    /// let mut heap = FixedBinaryHeap::new(&mut space);
+    /// heap.push(1).unwrap();
+    /// heap.push(2).unwrap();
+    /// assert_eq!(heap.push(3).unwrap_err().into_element(), 3);
+    /// # }
+    /// ```
+    pub fn push(&mut self, value: T) -> Result<(), CapacityError<T>> {
+        self.data.push(value)?;
+        let last = self.data.len() - 1;
+        self.sift_up(last);
+        Ok(())
+    }
+
+    /// Removes the greatest element from the heap and returns it, in
+    /// O(log N) time, or returns `None` if it is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedBinaryHeap;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut heap = FixedBinaryHeap::new(&mut space);
+    /// heap.push(1).unwrap();
+    /// heap.push(3).unwrap();
+    /// assert_eq!(heap.pop(), Some(3));
+    /// assert_eq!(heap.pop(), Some(1));
+    /// assert_eq!(heap.pop(), None);
+    /// # }
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        let len = self.data.len();
+        if len == 0 {
+            return None;
+        }
+        self.data.as_mut_slice().swap(0, len - 1);
+        let item = self.data.pop();
+        self.sift_down_range(0, len - 1);
+        item
+    }
+
+    /// Retains only the elements specified by the predicate, such as
+    /// cancelled timers, then restores the heap in O(N) time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedBinaryHeap;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 8]);
+    /// let mut heap = FixedBinaryHeap::new(&mut space);
+    /// for x in 1..7 {
+    ///     heap.push(x).unwrap();
+    /// }
+    /// heap.retain(|&x| x % 2 == 1);
+    /// assert_eq!(heap.into_sorted().as_slice(), &[1, 3, 5]);
+    /// # }
+    /// ```
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.data.retain(f);
+        self.rebuild();
+    }
+
+    /// Removes all elements from the heap.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.data.clear()
+    }
+
+    /// Moves the element at `pos` up until its parent is not less than it.
+    fn sift_up(&mut self, mut pos: usize) {
+        let data = self.data.as_mut_slice();
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+            if data[pos] <= data[parent] {
+                break;
+            }
+            data.swap(pos, parent);
+            pos = parent;
+        }
+    }
+
+    /// Moves the element at `pos` down until neither of its children within
+    /// `..end` is greater than it.
+    fn sift_down_range(&mut self, mut pos: usize, end: usize) {
+        let data = self.data.as_mut_slice();
+        loop {
+            let mut child = 2 * pos + 1;
+            if child >= end {
+                break;
+            }
+            if child + 1 < end && data[child + 1] > data[child] {
+                child += 1;
+            }
+            if data[pos] >= data[child] {
+                break;
+            }
+            data.swap(pos, child);
+            pos = child;
+        }
+    }
+
+    /// Rearranges all the elements into a heap.
+    fn rebuild(&mut self) {
+        let len = self.data.len();
+        for pos in (0..len / 2).rev() {
+            self.sift_down_range(pos, len);
+        }
+    }
+}
+
+impl<'a, T, S> From<FixedVec<'a, T, S>> for FixedBinaryHeap<'a, T, S>
+where
+    T: Ord,
+    S: Storage<T>,
+{
+    fn from(vec: FixedVec<'a, T, S>) -> Self {
+        FixedBinaryHeap::from_vec(vec)
+    }
+}
+
+impl<'a, T, S> fmt::Debug for FixedBinaryHeap<'a, T, S>
+where
+    T: Ord + fmt::Debug,
+    S: Storage<T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.data.fmt(f)
+    }
+}
+
+impl<'a, 'b, T, S> IntoIterator for &'b FixedBinaryHeap<'a, T, S>
+where
+    T: Ord,
+    S: Storage<T>,
+{
+    type Item = &'b T;
+    type IntoIter = Iter<'b, T>;
+
+    fn into_iter(self) -> Iter<'b, T> {
+        self.iter()
+    }
+}
+
+/// Mutable access to the greatest element of a `FixedBinaryHeap`.
+///
+/// This struct is created by `FixedBinaryHeap::peek_mut`. If the element is
+/// modified, the heap is restored when the guard is dropped.
+///
+/// While a modified element is borrowed, the heap only exposes that element,
+/// so leaking the guard with `mem::forget` leaks the other elements rather
+/// than leaving the heap out of order.
+pub struct PeekMut<'b, 'a: 'b, T: 'b + Ord, S: 'b>
+where
+    S: Storage<T>,
+{
+    heap: &'b mut FixedBinaryHeap<'a, T, S>,
+    /// The length of the heap, if it was shrunk to the peeked element
+    /// because that element may have been modified.
+    original_len: Option<usize>,
+}
+
+impl<'b, 'a, T, S> PeekMut<'b, 'a, T, S>
+where
+    T: Ord,
+    S: Storage<T>,
+{
+    /// Removes the peeked element from the heap and returns it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::{FixedBinaryHeap, PeekMut};
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut heap = FixedBinaryHeap::new(&mut space);
+    /// heap.push(1).unwrap();
+    /// heap.push(2).unwrap();
+    /// assert_eq!(PeekMut::pop(heap.peek_mut().unwrap()), 2);
+    /// assert_eq!(heap.len(), 1);
+    /// # }
+    /// ```
+    pub fn pop(mut this: PeekMut<'b, 'a, T, S>) -> T {
+        // The heap is restored by `pop`, so the guard has nothing to do.
+        if let Some(len) = this.original_len.take() {
+            unsafe { this.heap.data.set_len(len) };
+        }
+        this.heap.pop().unwrap()
+    }
+}
+
+impl<'b, 'a, T, S> ops::Deref for PeekMut<'b, 'a, T, S>
+where
+    T: Ord,
+    S: Storage<T>,
+{
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.heap.data[0]
+    }
+}
+
+impl<'b, 'a, T, S> ops::DerefMut for PeekMut<'b, 'a, T, S>
+where
+    T: Ord,
+    S: Storage<T>,
+{
+    fn deref_mut(&mut self) -> &mut T {
+        let len = self.heap.len();
+        if self.original_len.is_none() && len > 1 {
+            // Hide the rest of the heap until it is restored on drop.
+            unsafe { self.heap.data.set_len(1) };
+            self.original_len = Some(len);
+        }
+        &mut self.heap.data[0]
+    }
+}
+
+impl<'b, 'a, T, S> Drop for PeekMut<'b, 'a, T, S>
+where
+    T: Ord,
+    S: Storage<T>,
+{
+    fn drop(&mut self) {
+        if let Some(len) = self.original_len {
+            unsafe { self.heap.data.set_len(len) };
+            self.heap.sift_down_range(0, len);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::test_util::Droppable;
+    use super::FixedBinaryHeap;
+    use std::cell::Cell;
+    use std::cmp::Reverse;
+    use std::mem;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn test_pop_order() {
+        let input = [9u8, 2, 7, 4, 4, 0, 8, 1, 6, 3, 5];
+        let mut space = alloc_stack!([u8; 11]);
+        let mut heap = FixedBinaryHeap::new(&mut space);
+        for &x in &input {
+            heap.push(x).unwrap();
+        }
+        assert!(heap.is_full());
+        let mut popped = Vec::new();
+        while let Some(x) = heap.pop() {
+            popped.push(x);
+        }
+        let mut expected = input.to_vec();
+        expected.sort_by(|a, b| b.cmp(a));
+        assert_eq!(popped, expected);
+    }
+
+    #[test]
+    fn test_min_heap() {
+        let mut space = alloc_stack!([Reverse<u8>; 4], Reverse(0));
+        let mut heap = FixedBinaryHeap::new(&mut space);
+        for &x in &[3, 1, 2] {
+            heap.push(Reverse(x)).unwrap();
+        }
+        assert_eq!(heap.pop(), Some(Reverse(1)));
+        assert_eq!(heap.pop(), Some(Reverse(2)));
+    }
+
+    #[test]
+    fn test_peek_mut_untouched() {
+        let mut space = alloc_stack!([u8; 4]);
+        let mut heap = FixedBinaryHeap::new(&mut space);
+        heap.push(2).unwrap();
+        heap.push(1).unwrap();
+        assert_eq!(*heap.peek_mut().unwrap(), 2);
+        *heap.peek_mut().unwrap() = 0;
+        assert_eq!(heap.pop(), Some(1));
+        assert_eq!(heap.pop(), Some(0));
+    }
+
+    #[test]
+    fn test_peek_mut_forgotten() {
+        let mut space = alloc_stack!([u8; 4]);
+        let mut heap = FixedBinaryHeap::new(&mut space);
+        for &x in &[5, 3, 4] {
+            heap.push(x).unwrap();
+        }
+        let mut top = heap.peek_mut().unwrap();
+        *top = 1;
+        mem::forget(top);
+        // The rest of the heap is leaked rather than left out of order
+        assert_eq!(heap.len(), 1);
+        assert_eq!(heap.pop(), Some(1));
+        assert_eq!(heap.pop(), None);
+    }
+
+    #[test]
+    fn test_retain_and_drop() {
+        let drops = Cell::new(0);
+        {
+            let mut space = alloc_stack!(uninit [Droppable; 6]);
+            let mut heap = FixedBinaryHeap::from_uninit(&mut space);
+            for i in 0..6 {
+                heap.push(Droppable(i, &drops)).unwrap();
+            }
+            heap.retain(|x| x.0 != 5 && x.0 != 2);
+            assert_eq!(drops.get(), 2);
+            assert_eq!(heap.peek().map(|x| x.0), Some(4));
+            let sorted: Vec<u8> = heap.into_sorted().iter().map(|x| x.0).collect();
+            assert_eq!(sorted, &[0, 1, 3, 4]);
+        }
+        assert_eq!(drops.get(), 6);
+    }
+}
@@ -0,0 +1,204 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015-2016 Nick Stevens <nick@bitcurry.com>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! By-value iterator for `FixedVec`.
+
+use core::marker::PhantomData;
+use core::ptr;
+use core::slice;
+
+use super::{FixedVec, Storage};
+
+/// An iterator that moves elements out of a `FixedVec`.
+///
+/// This struct is created by the `into_iter` method on `FixedVec` (provided
+/// by the `IntoIterator` trait). It takes over the vector's storage, and
+/// drops any elements that were not yielded when it is dropped.
+pub struct IntoIter<'a, T, S = &'a mut [T]>
+where
+    S: Storage<T>,
+{
+    memory: S,
+    /// Index of the next element to yield from the front.
+    head: usize,
+    /// One past the index of the next element to yield from the back.
+    end: usize,
+    marker: PhantomData<(&'a (), T)>,
+}
+
+impl<'a, T, S> IntoIter<'a, T, S>
+where
+    S: Storage<T>,
+{
+    /// Returns the remaining items of this iterator as a slice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 10]);
+    /// let mut vec = FixedVec::new(&mut space);
+    /// vec.push_all(&[1, 2, 3]).unwrap();
+    ///
+    /// let mut iter = vec.into_iter();
+    /// assert_eq!(iter.as_slice(), &[1, 2, 3]);
+    /// iter.next();
+    /// assert_eq!(iter.as_slice(), &[2, 3]);
+    /// # }
+    /// ```
+    pub fn as_slice(&self) -> &[T] {
+        unsafe {
+            let p = self.memory.as_ptr().add(self.head);
+            slice::from_raw_parts(p, self.end - self.head)
+        }
+    }
+
+    /// Returns the remaining items of this iterator as a mutable slice.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe {
+            let p = self.memory.as_mut_ptr().add(self.head);
+            slice::from_raw_parts_mut(p, self.end - self.head)
+        }
+    }
+}
+
+impl<'a, T, S> Iterator for IntoIter<'a, T, S>
+where
+    S: Storage<T>,
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.head == self.end {
+            None
+        } else {
+            let item = unsafe { ptr::read(self.memory.as_ptr().add(self.head)) };
+            self.head += 1;
+            Some(item)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.head;
+        (len, Some(len))
+    }
+}
+
+impl<'a, T, S> DoubleEndedIterator for IntoIter<'a, T, S>
+where
+    S: Storage<T>,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        if self.head == self.end {
+            None
+        } else {
+            self.end -= 1;
+            Some(unsafe { ptr::read(self.memory.as_ptr().add(self.end)) })
+        }
+    }
+}
+
+impl<'a, T, S> ExactSizeIterator for IntoIter<'a, T, S> where S: Storage<T> {}
+
+impl<'a, T, S> Drop for IntoIter<'a, T, S>
+where
+    S: Storage<T>,
+{
+    fn drop(&mut self) {
+        let remaining = self.as_mut_slice() as *mut [T];
+        self.head = self.end;
+        unsafe { ptr::drop_in_place(remaining) };
+    }
+}
+
+impl<'a, T, S> IntoIterator for FixedVec<'a, T, S>
+where
+    S: Storage<T>,
+{
+    type Item = T;
+    type IntoIter = IntoIter<'a, T, S>;
+
+    fn into_iter(self) -> IntoIter<'a, T, S> {
+        // The elements now belong to the iterator.
+        let (memory, len) = self.into_inner();
+        IntoIter {
+            memory,
+            head: 0,
+            end: len,
+            marker: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::test_util::Droppable;
+    use super::super::{ArrayFixedVec, FixedVec};
+    use std::cell::Cell;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn test_into_iter_borrowed() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3, 4]).unwrap();
+        let mut iter = vec.into_iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next_back(), Some(4));
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.collect::<Vec<_>>(), &[2, 3]);
+    }
+
+    #[test]
+    fn test_into_iter_drops_remaining() {
+        let drops = Cell::new(0);
+        let mut vec: ArrayFixedVec<Droppable, 4> = ArrayFixedVec::new();
+        for i in 0..4 {
+            vec.push(Droppable(i, &drops)).unwrap();
+        }
+        let mut iter = vec.into_iter();
+        let first = iter.next().unwrap();
+        assert_eq!(first.0, 0);
+        assert_eq!(iter.as_slice().len(), 3);
+        drop(iter);
+        assert_eq!(drops.get(), 3);
+        drop(first);
+        assert_eq!(drops.get(), 4);
+    }
+
+    #[test]
+    fn test_into_iter_owned_return() {
+        fn make() -> ArrayFixedVec<String, 3> {
+            let mut vec = ArrayFixedVec::new();
+            vec.push("a".to_string()).unwrap();
+            vec.push("b".to_string()).unwrap();
+            vec
+        }
+        let collected: Vec<String> = make().into_iter().rev().collect();
+        assert_eq!(collected, &["b", "a"]);
+    }
+}
@@ -40,9 +40,26 @@
 //!
 //! # Differences from `std::vec::Vec`
 //!
-//! For now, `FixedVec` only works for types that implement `Copy`. This
-//! requirement will be lifted in the future, but for now it is the most
-//! straightforward way to get to a minimum viable product.
+//! `FixedVec` works for any element type. The backing memory is a slice of
+//! `MaybeUninit<T>`, and only the first `len()` slots are ever treated as
+//! initialized. Elements are dropped when they are removed from the vector or
+//! when the vector itself goes out of scope. For `Copy` types, a plain
+//! `&mut [T]` can be used as backing memory through `FixedVec::new`; all other
+//! types use `FixedVec::from_uninit`.
+//!
+//! `FixedVec` usually borrows its memory, but it can be backed by any type
+//! that implements `Storage`: borrowed slices, owned arrays, or (with the
+//! `alloc` feature) boxed slices. `ArrayFixedVec` is a convenient owned
+//! vector that keeps its elements inline, so it can be returned from a
+//! function, or stored in a struct or `static`.
+//!
+//! For FIFO use, `FixedDeque` is a ring buffer over the same kinds of
+//! storage, with O(1) pushes and pops at both ends. `FixedRing` is a FIFO
+//! built on it that can evict the oldest element instead of failing when it
+//! is full. `FixedBinaryHeap` is a priority queue over a `FixedVec`.
+//! `FixedSortedMap` and `FixedSortedSet` keep their contents sorted in a
+//! `FixedVec` for lookup by binary search. `FixedHashMap` is an open-addressing hash
+//! table over a buffer of `HashSlot`s, for larger maps that change often.
 //!
 //! Although every effort has been made to mimic the functionality of `Vec`,
 //! this is not a perfect clone. Specifically, functions that require memory
@@ -61,20 +78,38 @@
 //! The following functions have different signatures than their equivalents in
 //! `Vec`.
 //!
-//! * `new`: Self-explanatory - instantiating a different object
+//! * `new`, `from_uninit`: Self-explanatory - instantiating a different object
 //! * `push`, `push_all`, `insert`: Functions that add elements return a Result
-//!    indicating if the result was successful.
+//!   indicating if the result was successful. On failure, the `CapacityError`
+//!   hands back the rejected element.
+//! * `append`: Returns a Result, and leaves `other` untouched if its elements
+//!   do not fit.
+//! * `split_off`: Moves the tail into a caller-supplied `FixedVec` instead of
+//!   allocating a new vector.
+//! * `resize_with`, `extend_from_within`: Return a Result, and leave the
+//!   vector untouched if the new elements do not fit.
+//! * `splice`: Returns a Result, and requires the replacement iterator to
+//!   report its exact length so that the space can be checked up front.
 //! * `map_in_place`: Similar to `Vec` `map_in_place`, except there is no
-//!    coercion of the types.
+//!   coercion of the types.
 //!
 //! ## Functions in `FixedVec` not in `Vec`
 //!
+//! * `from_parts`, `from_full`: Wrap a slice that already holds elements.
+//! * `into_inner`, `into_slice`: Give back the storage, or the borrowed slice
+//!   of live elements.
 //! * `available`: Convenience function for checking remaining space.
-//! * `iter`: `FixedVec` cannot implement `IntoIterator` because the type
-//!   signature of that trait requires taking ownership of the underlying
-//!   struct. Since `FixedVec` keeps a reference to its backing store,
-//!   ownership is not its to give. It's possible I'm just being dense and this
-//!   is possible - I'd love to be proven wrong.
+//! * `is_full`: Convenience function for checking if there is no space left.
+//! * `reserve_slot`, `push_with`: Initialise the next element in place, for
+//!   `MaybeUninit` storage.
+//! * `fill_with`: Lets a driver write into the unused space and commit the
+//!   filled elements.
+//! * `insert_slice`, `remove_range`: Insert or remove a block of elements at
+//!   an index with a single shift.
+//!
+//! `FixedVec` implements `IntoIterator` by value. The resulting `IntoIter`
+//! takes over the vector's storage - for a borrowed slice, it keeps the
+//! borrow - and yields the live elements.
 //!
 //! ## Functions in `Vec` excluded from `FixedVec`
 //!
@@ -89,11 +124,6 @@
 //! * `reserve_exact`
 //! * `shrink_to_fit`
 //! * `into_boxed_slice`
-//! * `truncate`
-//! * `set_len`
-//! * `append`
-//! * `drain`
-//! * `split_off`
 //!
 //! # Example
 //!
@@ -124,16 +154,38 @@
 //! Rust book section ["No stdlib"](https://doc.rust-lang.org/book/no-stdlib.html)
 //! for instructions on building executables using only libcore.
 
+use core::borrow::{Borrow, BorrowMut};
+use core::cmp::Ordering;
+use core::fmt;
 use core::hash::{Hash, Hasher};
+use core::iter;
+use core::marker::PhantomData;
+use core::mem;
+use core::mem::MaybeUninit;
 use core::ops;
+use core::ops::{Bound, RangeBounds};
+use core::ptr;
+use core::slice;
+use core::slice::SliceIndex;
 
 #[cfg(test)]
 #[macro_use]
 extern crate std;
 
+#[cfg(all(feature = "std", not(test)))]
+extern crate std;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 /// Convenience macro for use with `FixedVec`. Allocates the specified number
 /// of elements of specified type on the stack.
 ///
+/// By default, the elements are filled with `Default::default()`. A fill
+/// expression can be given instead for types without a `Default`. With the
+/// `uninit` form, the space is an array of `MaybeUninit`, which works for any
+/// type and is given to `FixedVec::from_uninit`.
+///
 /// # Example
 ///
 /// ```
@@ -145,6 +197,15 @@ extern crate std;
 ///
 /// // Give the space to a `FixedVec`, which manages it from here on out
 /// let vec = FixedVec::new(&mut space);
+///
+/// // Fill the space with an explicit value
+/// let mut space = alloc_stack!([(u8, char); 4], (0, ' '));
+/// let vec = FixedVec::new(&mut space);
+///
+/// // Leave the space uninitialized, so any type can be stored
+/// let mut space = alloc_stack!(uninit [String; 4]);
+/// let mut vec = FixedVec::from_uninit(&mut space);
+/// vec.push("hello".to_string()).unwrap();
 /// # }
 /// ```
 #[macro_export]
@@ -153,24 +214,250 @@ macro_rules! alloc_stack {
         let space: [$item_type; $len] = [Default::default(); $len];
         space
     }};
+    ([$item_type:ty; $len:expr], $fill:expr) => {{
+        let space: [$item_type; $len] = [$fill; $len];
+        space
+    }};
+    (uninit [$item_type:ty; $len:expr]) => {{
+        // An array of `MaybeUninit` does not require initialization.
+        let space: [$crate::__private::MaybeUninit<$item_type>; $len] =
+            unsafe { $crate::__private::MaybeUninit::uninit().assume_init() };
+        space
+    }};
+}
+
+/// Creates an `ArrayFixedVec`, which owns its memory, in one go.
+///
+/// `fixedvec![T; N]` creates an empty vector with space for `N` elements of
+/// type `T`. `fixedvec![N => a, b, c]` creates a vector with space for `N`
+/// elements that holds the given elements.
+///
+/// # Panics
+///
+/// Panics if more than `N` elements are given.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate fixedvec;
+/// # fn main() {
+/// let mut vec = fixedvec![u32; 16];
+/// vec.push(1).unwrap();
+/// assert_eq!(vec.capacity(), 16);
+///
+/// let vec = fixedvec![16 => 1, 2, 3];
+/// assert_eq!(vec.capacity(), 16);
+/// assert_eq!(vec.as_slice(), &[1, 2, 3]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! fixedvec {
+    ($item_type:ty; $len:expr) => {
+        $crate::ArrayFixedVec::<$item_type, { $len }>::new()
+    };
+    ($len:expr => $($element:expr),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut vec = $crate::ArrayFixedVec::<_, { $len }>::new();
+        $(
+            vec.push($element).expect("fixedvec!: too many elements");
+        )*
+        vec
+    }};
 }
 
-pub type Result<T> = core::result::Result<T, ErrorKind>;
+/// Declares a static buffer and hands it out as a `FixedVec<'static, T>`,
+/// at most once.
+///
+/// Each use of the macro has its own buffer. The first time it is evaluated,
+/// it returns `Some`; every later evaluation returns `None`, so the buffer
+/// can never be borrowed twice. An atomic flag guards the buffer, so the
+/// target must support atomic compare-and-swap.
+///
+/// The fill expression must be usable in a static initializer. With the
+/// `uninit` form, the buffer is an array of `MaybeUninit`, which works for
+/// any type and returns a `FixedVec` backed by `&'static mut [MaybeUninit<T>]`.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate fixedvec;
+/// # use fixedvec::FixedVec;
+/// fn event_buffer() -> Option<FixedVec<'static, u8>> {
+///     alloc_static!([u8; 64], 0)
+/// }
+///
+/// # fn main() {
+/// let mut events = event_buffer().unwrap();
+/// events.push(1).unwrap();
+///
+/// // The buffer is already taken
+/// assert!(event_buffer().is_none());
+///
+/// let names = alloc_static!(uninit [String; 4]).unwrap();
+/// assert_eq!(names.capacity(), 4);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! alloc_static {
+    ([$item_type:ty; $len:expr], $fill:expr) => {{
+        static TAKEN: $crate::__private::AtomicBool = $crate::__private::AtomicBool::new(false);
+        static mut SPACE: [$item_type; $len] = [$fill; $len];
+        if TAKEN.swap(true, $crate::__private::Ordering::AcqRel) {
+            None
+        } else {
+            // The flag guarantees this is the only reference to `SPACE`.
+            let space = unsafe { &mut *$crate::__private::addr_of_mut!(SPACE) };
+            Some($crate::FixedVec::new(space))
+        }
+    }};
+    (uninit [$item_type:ty; $len:expr]) => {{
+        static TAKEN: $crate::__private::AtomicBool = $crate::__private::AtomicBool::new(false);
+        static mut SPACE: [$crate::__private::MaybeUninit<$item_type>; $len] =
+            unsafe { $crate::__private::MaybeUninit::uninit().assume_init() };
+        if TAKEN.swap(true, $crate::__private::Ordering::AcqRel) {
+            None
+        } else {
+            // The flag guarantees this is the only reference to `SPACE`.
+            let space = unsafe { &mut *$crate::__private::addr_of_mut!(SPACE) };
+            Some($crate::FixedVec::from_uninit(space))
+        }
+    }};
+}
 
-#[derive(Debug)]
-pub enum ErrorKind {
-    NoSpace,
+#[doc(hidden)]
+pub mod __private {
+    pub use core::mem::MaybeUninit;
+    pub use core::ptr::addr_of_mut;
+    pub use core::sync::atomic::{AtomicBool, Ordering};
 }
 
-#[derive(Debug)]
-pub struct FixedVec<'a, T: 'a + Copy> {
-    memory: &'a mut [T],
+mod array;
+mod deque;
+mod drain;
+mod error;
+mod extract_if;
+mod fnv;
+mod hash_map;
+mod heap;
+mod into_iter;
+mod ring;
+mod slot;
+mod sorted_map;
+mod sorted_set;
+mod storage;
+
+pub use array::ArrayFixedVec;
+pub use deque::{DequeIter, DequeIterMut, FixedDeque};
+pub use drain::{Drain, Splice};
+pub use error::{CapacityError, ErrorKind, Result};
+pub use extract_if::ExtractIf;
+pub use fnv::{FnvBuildHasher, FnvHasher};
+pub use hash_map::{
+    FixedHashMap, HashEntry, HashMapIter, HashMapIterMut, HashOccupiedEntry, HashSlot,
+    HashVacantEntry,
+};
+pub use heap::{FixedBinaryHeap, PeekMut};
+pub use into_iter::IntoIter;
+pub use ring::{FixedRing, OverflowPolicy, Pushed};
+pub use slot::ReservedSlot;
+pub use sorted_map::{
+    FixedSortedMap, SortedEntry, SortedMapIter, SortedMapIterMut, SortedOccupiedEntry,
+    SortedVacantEntry,
+};
+pub use sorted_set::FixedSortedSet;
+pub use storage::Storage;
+
+pub struct FixedVec<'a, T, S = &'a mut [T]>
+where
+    S: Storage<T>,
+{
+    memory: S,
     len: usize,
+    marker: PhantomData<(&'a (), T)>,
 }
 
 pub use core::slice::Iter;
 pub use core::slice::IterMut;
 
+/// The elements of an iterator that did not fit in a `FixedVec`, as returned
+/// by `FixedVec::try_extend`.
+pub type Leftover<I> = iter::Chain<iter::Once<<I as Iterator>::Item>, I>;
+
+/// Resolves `range` against a vector of length `len`, returning its start
+/// and end indices.
+///
+/// Panics if the start is greater than the end, or the end is greater than
+/// `len`.
+fn resolve_range<R>(range: R, len: usize) -> (usize, usize)
+where
+    R: RangeBounds<usize>,
+{
+    let start = match range.start_bound() {
+        Bound::Included(&n) => n,
+        Bound::Excluded(&n) => n
+            .checked_add(1)
+            .expect("attempted to index slice from after maximum usize"),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&n) => n
+            .checked_add(1)
+            .expect("attempted to index slice up to maximum usize"),
+        Bound::Excluded(&n) => n,
+        Bound::Unbounded => len,
+    };
+    assert!(start <= end);
+    assert!(end <= len);
+    (start, end)
+}
+
+/// Converts a range of keys into the start and end indices of the elements
+/// of `slice`, which is sorted by `key`, whose keys fall within it.
+///
+/// Panics if the start of the range is greater than its end, or if both ends
+/// are excluded and equal, whatever the contents of `slice`.
+fn resolve_key_range<T, K, Q, R, F>(slice: &[T], range: R, key: F) -> (usize, usize)
+where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+    R: RangeBounds<Q>,
+    F: Fn(&T) -> &K,
+{
+    match (range.start_bound(), range.end_bound()) {
+        (Bound::Excluded(s), Bound::Excluded(e)) if s == e => {
+            panic!("range start and end are equal and excluded")
+        }
+        (Bound::Included(s) | Bound::Excluded(s), Bound::Included(e) | Bound::Excluded(e))
+            if s > e =>
+        {
+            panic!("range start is greater than range end")
+        }
+        _ => {}
+    }
+    let search = |q: &Q| slice.binary_search_by(|e| key(e).borrow().cmp(q));
+    let start = match range.start_bound() {
+        Bound::Included(q) => match search(q) {
+            Ok(i) | Err(i) => i,
+        },
+        Bound::Excluded(q) => match search(q) {
+            Ok(i) => i + 1,
+            Err(i) => i,
+        },
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(q) => match search(q) {
+            Ok(i) => i + 1,
+            Err(i) => i,
+        },
+        Bound::Excluded(q) => match search(q) {
+            Ok(i) | Err(i) => i,
+        },
+        Bound::Unbounded => slice.len(),
+    };
+    (start, end)
+}
+
 impl<'a, T> FixedVec<'a, T>
 where
     T: 'a + Copy,
@@ -178,6 +465,10 @@ where
     /// Create a new `FixedVec` from the provided slice, in the process taking
     /// ownership of the slice.
     ///
+    /// Only `Copy` types can be stored in a plain slice, since the values
+    /// already in it are overwritten without being dropped. Use
+    /// `from_uninit` for other types.
+    ///
     /// # Example
     ///
     /// ```
@@ -193,12 +484,170 @@ where
     /// ```
     ///
     pub fn new(memory: &'a mut [T]) -> Self {
+        FixedVec::from_storage(memory)
+    }
+
+    /// Create a new `FixedVec` from the provided slice, treating the first
+    /// `len` elements as already pushed.
+    ///
+    /// This wraps a buffer that was filled by other means, such as a
+    /// bootloader or a read from flash. If `len` is greater than the length
+    /// of the slice, an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedVec;
+    /// let mut space = [1u8, 2, 3, 0, 0];
+    /// let mut vec = FixedVec::from_parts(&mut space, 3).unwrap();
+    /// assert_eq!(vec.as_slice(), &[1, 2, 3]);
+    /// vec.push(4).unwrap();
+    /// assert_eq!(vec.as_slice(), &[1, 2, 3, 4]);
+    ///
+    /// let mut small = [0u8; 2];
+    /// assert!(FixedVec::from_parts(&mut small, 3).is_err());
+    /// ```
+    pub fn from_parts(memory: &'a mut [T], len: usize) -> Result<Self> {
+        if len > memory.len() {
+            return Err(ErrorKind::IndexOutOfBounds);
+        }
+        let mut vec = FixedVec::from_storage(memory);
+        vec.len = len;
+        Ok(vec)
+    }
+
+    /// Create a new `FixedVec` from the provided slice, treating all of it as
+    /// already pushed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedVec;
+    /// let mut space = [1u8, 2, 3];
+    /// let vec = FixedVec::from_full(&mut space);
+    /// assert_eq!(vec.as_slice(), &[1, 2, 3]);
+    /// assert!(vec.is_full());
+    /// ```
+    pub fn from_full(memory: &'a mut [T]) -> Self {
+        let len = memory.len();
+        let mut vec = FixedVec::from_storage(memory);
+        vec.len = len;
+        vec
+    }
+
+    /// Consumes the vector, returning the live elements as a slice that
+    /// keeps the original borrow.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedVec;
+    /// let mut space = [0u8; 8];
+    /// let live: &mut [u8] = {
+    ///     let mut vec = FixedVec::new(&mut space);
+    ///     vec.push_all(&[1, 2, 3]).unwrap();
+    ///     vec.into_slice()
+    /// };
+    /// assert_eq!(live, &[1, 2, 3]);
+    /// ```
+    pub fn into_slice(self) -> &'a mut [T] {
+        let (memory, len) = self.into_inner();
+        &mut memory[..len]
+    }
+}
+
+impl<'a, T> FixedVec<'a, T, &'a mut [MaybeUninit<T>]> {
+    /// Create a new `FixedVec` from a slice of possibly-uninitialized memory.
+    ///
+    /// This works for any element type, including types that are not `Copy`
+    /// or that implement `Drop`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// use std::mem::MaybeUninit;
+    ///
+    /// # fn main() {
+    /// let mut space: [MaybeUninit<String>; 4] = unsafe { MaybeUninit::uninit().assume_init() };
+    /// let mut vec = FixedVec::from_uninit(&mut space);
+    /// assert_eq!(vec.capacity(), 4);
+    ///
+    /// vec.push("hello".to_string()).unwrap();
+    /// assert_eq!(vec.as_slice(), &["hello".to_string()]);
+    /// # }
+    /// ```
+    pub fn from_uninit(memory: &'a mut [MaybeUninit<T>]) -> Self {
+        FixedVec::from_storage(memory)
+    }
+}
+
+impl<'a, T, S> FixedVec<'a, T, S>
+where
+    S: Storage<T>,
+{
+    /// Create a new `FixedVec` from any kind of `Storage`, such as an owned
+    /// array or a boxed slice.
+    ///
+    /// This is a `const fn`, so a vector with owned storage can be created in
+    /// a constant or a static initializer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedVec;
+    /// use std::mem::MaybeUninit;
+    ///
+    /// let space: [MaybeUninit<String>; 4] = unsafe { MaybeUninit::uninit().assume_init() };
+    /// let mut vec = FixedVec::from_storage(space);
+    /// assert_eq!(vec.capacity(), 4);
+    ///
+    /// vec.push("hello".to_string()).unwrap();
+    /// assert_eq!(vec.as_slice(), &["hello".to_string()]);
+    /// ```
+    pub const fn from_storage(memory: S) -> Self {
         FixedVec {
-            memory: memory,
+            memory,
             len: 0,
+            marker: PhantomData,
         }
     }
 
+    /// Consumes the vector, returning its storage and length.
+    ///
+    /// The live elements are left in the first `len` slots of the storage
+    /// and are not dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedVec;
+    /// let mut space = [0u8; 8];
+    /// let mut vec = FixedVec::new(&mut space);
+    /// vec.push_all(&[1, 2, 3]).unwrap();
+    ///
+    /// let (memory, len) = vec.into_inner();
+    /// assert_eq!(len, 3);
+    /// assert_eq!(memory.len(), 8);
+    /// assert_eq!(&memory[..len], &[1, 2, 3]);
+    /// ```
+    pub fn into_inner(self) -> (S, usize) {
+        let vec = mem::ManuallyDrop::new(self);
+        let memory = unsafe { ptr::read(&vec.memory) };
+        (memory, vec.len)
+    }
+
+    #[inline]
+    fn as_ptr(&self) -> *const T {
+        self.memory.as_ptr()
+    }
+
+    #[inline]
+    fn as_mut_ptr(&mut self) -> *mut T {
+        self.memory.as_mut_ptr()
+    }
+
     /// Returns the capacity of the vector.
     ///
     /// # Example
@@ -216,7 +665,7 @@ where
     /// ```
     #[inline]
     pub fn capacity(&self) -> usize {
-        self.memory.len()
+        self.memory.capacity()
     }
 
     /// Returns the number of elements in the vector. This will always be
@@ -236,7 +685,7 @@ where
     /// # }
     /// ```
     #[inline]
-    pub fn len(&self) -> usize {
+    pub const fn len(&self) -> usize {
         self.len
     }
 
@@ -278,10 +727,31 @@ where
     /// assert!(!vec.is_empty());
     /// # }
     #[inline]
-    pub fn is_empty(&self) -> bool {
+    pub const fn is_empty(&self) -> bool {
         self.len == 0
     }
 
+    /// Returns `true` if the vector has no space left for more elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 2]);
+    /// let mut vec = FixedVec::new(&mut space);
+    /// vec.push(1).unwrap();
+    /// assert!(!vec.is_full());
+    /// vec.push(2).unwrap();
+    /// assert!(vec.is_full());
+    /// # }
+    /// ```
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len == self.capacity()
+    }
+
     /// Extracts a slice containing the entire vector.
     ///
     /// Equivalent to `&s[..]`.
@@ -300,7 +770,7 @@ where
     /// # }
     #[inline]
     pub fn as_slice(&self) -> &[T] {
-        &self.memory[..self.len]
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.len) }
     }
 
     /// Extracts a mutable slice of the entire vector.
@@ -323,12 +793,46 @@ where
     /// # }
     #[inline]
     pub fn as_mut_slice(&mut self) -> &mut [T] {
-        &mut self.memory[..self.len]
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
+    }
+
+    /// Returns the unused slots at the end of the vector's memory.
+    ///
+    /// For storage of plain `T`, the slots are `&mut [T]`, and `fill_with` can
+    /// commit them safely. For `MaybeUninit<T>` storage, write the slots and
+    /// then commit them with `set_len`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedVec;
+    /// use std::mem::MaybeUninit;
+    ///
+    /// let mut space: [MaybeUninit<String>; 4] = unsafe { MaybeUninit::uninit().assume_init() };
+    /// let mut vec = FixedVec::from_uninit(&mut space);
+    /// vec.push("a".to_string()).unwrap();
+    ///
+    /// let spare = vec.spare_capacity_mut();
+    /// assert_eq!(spare.len(), 3);
+    /// spare[0].write("b".to_string());
+    /// unsafe { vec.set_len(2) };
+    /// assert_eq!(vec.as_slice(), &["a", "b"]);
+    /// ```
+    #[inline]
+    pub fn spare_capacity_mut(&mut self) -> &mut [S::Slot] {
+        let len = self.len;
+        let available = self.available();
+        unsafe {
+            let p = self.as_mut_ptr().add(len) as *mut S::Slot;
+            slice::from_raw_parts_mut(p, available)
+        }
     }
 
     /// Inserts an element at position `index` within the vector, shifting all
     /// elements after position `i` one position to the right.
     ///
+    /// If the vector is full, `element` is handed back in the error.
+    ///
     /// # Panics
     ///
     /// Panics if `index` is greater than the vector's length.
@@ -352,26 +856,26 @@ where
     /// assert_eq!(vec.as_slice(), &[1, 15, 2, 3, 16]);
     ///
     /// // Cannot insert if there is not enough capacity
-    /// assert!(vec.insert(2, 17).is_err());
+    /// assert_eq!(vec.insert(2, 17).unwrap_err().into_element(), 17);
     /// # }
-    pub fn insert(&mut self, index: usize, element: T) -> Result<()> {
+    pub fn insert(
+        &mut self,
+        index: usize,
+        element: T,
+    ) -> core::result::Result<(), CapacityError<T>> {
         assert!(index <= self.len);
         if index == self.len || self.len == 0 {
             self.push(element)
         } else if self.available() >= 1 {
-            self.len += 1;
-            let mut i = self.len;
-            loop {
-                if i == index {
-                    break;
-                }
-                self.memory[i] = self.memory[i - 1];
-                i -= 1;
+            let p = self.as_mut_ptr();
+            unsafe {
+                ptr::copy(p.add(index), p.add(index + 1), self.len - index);
+                ptr::write(p.add(index), element);
             }
-            self.memory[index] = element;
+            self.len += 1;
             Ok(())
         } else {
-            Err(ErrorKind::NoSpace)
+            Err(CapacityError::new(element))
         }
     }
 
@@ -402,16 +906,62 @@ where
     /// # }
     pub fn remove(&mut self, index: usize) -> T {
         assert!(index < self.len);
-        let ret = self.memory[index];
+        let p = self.as_mut_ptr();
         self.len -= 1;
-        for i in index..self.len {
-            self.memory[i] = self.memory[i + 1];
+        unsafe {
+            let ret = ptr::read(p.add(index));
+            ptr::copy(p.add(index + 1), p.add(index), self.len - index);
+            ret
+        }
+    }
+
+    /// Removes the elements in the specified range from the vector, dropping
+    /// them and shifting the elements after the range to the left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end, or if the end
+    /// is greater than the length of the vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 10]);
+    /// let mut vec = FixedVec::new(&mut space);
+    ///
+    /// vec.push_all(&[1, 2, 3, 4, 5, 6]).unwrap();
+    /// vec.remove_range(1..4);
+    /// assert_eq!(vec.as_slice(), &[1, 5, 6]);
+    /// vec.remove_range(1..);
+    /// assert_eq!(vec.as_slice(), &[1]);
+    /// # }
+    /// ```
+    pub fn remove_range<R>(&mut self, range: R)
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = resolve_range(range, self.len);
+        if start == end {
+            return;
         }
-        ret
+        // The tail is leaked rather than double-dropped if a drop panics.
+        let tail = self.len - end;
+        self.len = start;
+        let p = self.as_mut_ptr();
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(p.add(start), end - start));
+            ptr::copy(p.add(end), p.add(start), tail);
+        }
+        self.len = start + tail;
     }
 
     /// Appends an element to the back of the vector.
     ///
+    /// If the vector is full, `value` is handed back in the error.
+    ///
     /// # Example
     ///
     /// ```
@@ -428,17 +978,18 @@ where
     /// assert_eq!(vec.as_slice(), &[1, 2, 3]);
     ///
     /// // Attempting to push a full vector results in an error
-    /// assert!(vec.push(4).is_err());
+    /// assert_eq!(vec.push(4).unwrap_err().into_element(), 4);
     /// # }
     /// ```
     #[inline]
-    pub fn push(&mut self, value: T) -> Result<()> {
+    pub fn push(&mut self, value: T) -> core::result::Result<(), CapacityError<T>> {
         if self.available() >= 1 {
-            self.memory[self.len] = value;
+            let len = self.len;
+            unsafe { ptr::write(self.as_mut_ptr().add(len), value) };
             self.len += 1;
             Ok(())
         } else {
-            Err(ErrorKind::NoSpace)
+            Err(CapacityError::new(value))
         }
     }
 
@@ -463,13 +1014,13 @@ where
     pub fn pop(&mut self) -> Option<T> {
         if self.len > 0 {
             self.len -= 1;
-            Some(self.memory[self.len])
+            Some(unsafe { ptr::read(self.as_ptr().add(self.len)) })
         } else {
             None
         }
     }
 
-    /// Copies all elements from slice `other` to this vector.
+    /// Clears the vector, removing all values.
     ///
     /// # Example
     ///
@@ -477,32 +1028,23 @@ where
     /// # #[macro_use] extern crate fixedvec;
     /// # use fixedvec::FixedVec;
     /// # fn main() {
-    /// let mut space = alloc_stack!([u8; 5]);
+    /// let mut space = alloc_stack!([u8; 10]);
     /// let mut vec = FixedVec::new(&mut space);
-    ///
-    /// // All elements are pushed to vector
-    /// vec.push_all(&[1, 2, 3, 4]).unwrap();
-    /// assert_eq!(vec.as_slice(), &[1, 2, 3, 4]);
-    ///
-    /// // If there is insufficient space, NO values are pushed
-    /// assert!(vec.push_all(&[5, 6, 7]).is_err());
-    /// assert_eq!(vec.as_slice(), &[1, 2, 3, 4]);
+    /// vec.push_all(&[1, 2, 3]).unwrap();
+    /// assert_eq!(vec.len(), 3);
+    /// vec.clear();
+    /// assert_eq!(vec.len(), 0);
     /// # }
     /// ```
-    #[inline]
-    pub fn push_all(&mut self, other: &[T]) -> Result<()> {
-        if other.len() > self.available() {
-            Err(ErrorKind::NoSpace)
-        } else {
-            for item in other.iter() {
-                self.memory[self.len] = *item;
-                self.len += 1;
-            }
-            Ok(())
-        }
+    pub fn clear(&mut self) {
+        self.truncate(0)
     }
 
-    /// Clears the vector, removing all values.
+    /// Shortens the vector, keeping the first `len` elements and dropping the
+    /// rest.
+    ///
+    /// If `len` is greater than the vector's current length, this has no
+    /// effect.
     ///
     /// # Example
     ///
@@ -512,14 +1054,50 @@ where
     /// # fn main() {
     /// let mut space = alloc_stack!([u8; 10]);
     /// let mut vec = FixedVec::new(&mut space);
-    /// vec.push_all(&[1, 2, 3]).unwrap();
-    /// assert_eq!(vec.len(), 3);
-    /// vec.clear();
-    /// assert_eq!(vec.len(), 0);
+    /// vec.push_all(&[1, 2, 3, 4]).unwrap();
+    /// vec.truncate(2);
+    /// assert_eq!(vec.as_slice(), &[1, 2]);
+    /// vec.truncate(5);
+    /// assert_eq!(vec.as_slice(), &[1, 2]);
     /// # }
     /// ```
-    pub fn clear(&mut self) {
-        self.len = 0
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        let tail = self.len - len;
+        self.len = len;
+        unsafe {
+            let p = self.as_mut_ptr().add(len);
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(p, tail));
+        }
+    }
+
+    /// Forces the length of the vector to `new_len`.
+    ///
+    /// This does not drop or initialize any elements. It is intended for
+    /// committing elements that were written to the backing memory by other
+    /// means, such as a DMA transfer.
+    ///
+    /// # Safety
+    ///
+    /// `new_len` must be less than or equal to `capacity()`, and the elements
+    /// at `len()..new_len` must be initialized.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedVec;
+    /// let mut space = [0u8; 4];
+    /// let mut vec = FixedVec::new(&mut space);
+    ///
+    /// // Every slot of a `[u8]` holds a valid `u8`
+    /// unsafe { vec.set_len(4) };
+    /// assert_eq!(vec.as_slice(), &[0, 0, 0, 0]);
+    /// ```
+    #[inline]
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        self.len = new_len;
     }
 
     /// Applies the function `f` to all elements in the vector, mutating the
@@ -543,8 +1121,8 @@ where
     where
         F: Fn(&mut T),
     {
-        for i in 0..self.len {
-            f(&mut self.memory[i]);
+        for item in self.as_mut_slice() {
+            f(item);
         }
     }
 
@@ -569,9 +1147,8 @@ where
     /// # }
     /// ```
     #[inline]
-    pub fn iter(&self) -> Iter<T> {
-        let (slice, _) = self.memory.split_at(self.len);
-        slice.iter()
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.as_slice().iter()
     }
 
     /// Provides a mutable forward iterator.
@@ -594,9 +1171,8 @@ where
     /// # }
     /// ```
     #[inline]
-    pub fn iter_mut(&mut self) -> IterMut<T> {
-        let (slice, _) = self.memory.split_at_mut(self.len);
-        slice.iter_mut()
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.as_mut_slice().iter_mut()
     }
 
     /// Removes an element from anywhere in the vector and returns it,
@@ -624,22 +1200,16 @@ where
     /// ```
     pub fn swap_remove(&mut self, index: usize) -> T {
         assert!(index < self.len);
-        if self.len == 1 {
-            self.remove(0)
-        } else {
-            let removed = self.memory[index];
-            self.memory[index] = self.pop().unwrap();
-            removed
-        }
+        let last = self.len - 1;
+        self.as_mut_slice().swap(index, last);
+        self.pop().unwrap()
     }
 
-    /// Resizes the vector in-place so that `len()` is equal to `new_len`.
-    ///
-    /// New elements (if needed) are cloned from `value`.
-    ///
-    /// # Panics
+    /// Retains only the elements specified by the predicate.
     ///
-    /// Panics if `new_len` is greater than capacity
+    /// In other words, remove all elements `e` such that `f(&e)` returns
+    /// false. This method operates in-place, in O(N) time, and preserves the
+    /// order of the retained elements.
     ///
     /// # Example
     ///
@@ -650,30 +1220,23 @@ where
     /// let mut space = alloc_stack!([u8; 10]);
     /// let mut vec = FixedVec::new(&mut space);
     ///
-    /// assert_eq!(vec.len(), 0);
-    /// vec.resize(5, 255);
-    /// assert_eq!(vec.as_slice(), &[255, 255, 255, 255, 255]);
-    /// vec.resize(2, 0);
-    /// assert_eq!(vec.as_slice(), &[255, 255]);
+    /// vec.push_all(&[1, 2, 3, 4]).unwrap();
+    /// vec.retain(|&x| x%2 == 0);
+    /// assert_eq!(vec.as_slice(), &[2, 4]);
     /// # }
     /// ```
-    pub fn resize(&mut self, new_len: usize, value: T) {
-        assert!(new_len <= self.capacity());
-        if new_len <= self.len {
-            self.len = new_len;
-        } else {
-            for i in self.memory[self.len..new_len].iter_mut() {
-                *i = Clone::clone(&value);
-            }
-            self.len = new_len;
-        }
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.retain_mut(|x| f(x))
     }
 
-    /// Retains only the elements specified by the predicate.
+    /// Retains only the elements specified by the predicate, passing a
+    /// mutable reference to each element.
     ///
-    /// In other words, remove all elements `e` such that `f(&e)` returns
-    /// false. This method operates in-place, in O(N) time, and preserves the
-    /// order of the retained elements.
+    /// In other words, remove all elements `e` such that `f(&mut e)` returns
+    /// false. The elements are visited exactly once, in order.
     ///
     /// # Example
     ///
@@ -685,27 +1248,51 @@ where
     /// let mut vec = FixedVec::new(&mut space);
     ///
     /// vec.push_all(&[1, 2, 3, 4]).unwrap();
-    /// vec.retain(|&x| x%2 == 0);
-    /// assert_eq!(vec.as_slice(), &[2, 4]);
+    /// let mut removed = 0;
+    /// vec.retain_mut(|x| {
+    ///     *x *= 10;
+    ///     if *x > 20 {
+    ///         removed += 1;
+    ///         false
+    ///     } else {
+    ///         true
+    ///     }
+    /// });
+    /// assert_eq!(vec.as_slice(), &[10, 20]);
+    /// assert_eq!(removed, 2);
     /// # }
     /// ```
-    pub fn retain<F>(&mut self, f: F)
+    pub fn retain_mut<F>(&mut self, mut f: F)
     where
-        F: Fn(&T) -> bool,
+        F: FnMut(&mut T) -> bool,
     {
+        // Elements are leaked rather than double-dropped if `f` panics.
+        let len = self.len;
+        self.len = 0;
+        let p = self.as_mut_ptr();
         let mut head: usize = 0;
-        let mut tail: usize = 0;
-        loop {
-            if head >= self.len {
-                break;
+        unsafe {
+            // Nothing needs to move until the first element is removed.
+            while head < len && f(&mut *p.add(head)) {
+                head += 1;
             }
-            if f(&self.memory[head]) {
-                self.memory[tail] = self.memory[head];
-                tail += 1;
+            let mut tail = head;
+            if head < len {
+                ptr::drop_in_place(p.add(head));
+                head += 1;
             }
-            head += 1;
+            while head < len {
+                let cur = p.add(head);
+                if f(&mut *cur) {
+                    ptr::copy_nonoverlapping(cur, p.add(tail), 1);
+                    tail += 1;
+                } else {
+                    ptr::drop_in_place(cur);
+                }
+                head += 1;
+            }
+            self.len = tail;
         }
-        self.len = tail;
     }
 
     /// Returns a reference to the element at the given index, or `None` if the
@@ -758,6 +1345,10 @@ where
     /// bounds checking. Note that the result of an invalid index is undefined,
     /// and may not panic.
     ///
+    /// # Safety
+    ///
+    /// `index` must be less than `len()`.
+    ///
     /// # Example
     ///
     /// ```
@@ -782,34 +1373,793 @@ where
     /// doing bounds checking. Note that the result of an invalid index is
     /// undefined, and may not panic.
     ///
+    /// # Safety
+    ///
+    /// `index` must be less than `len()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 10]);
+    /// let mut vec = FixedVec::new(&mut space);
+    ///
+    /// vec.push_all(&[10, 40, 30]).unwrap();
+    /// {
+    ///     let mut x = unsafe { vec.get_unchecked_mut(1) };
+    ///     *x = 50;
+    /// }
+    /// assert_eq!(Some(&50), vec.get(1));
+    ///
+    /// // Index beyond bounds is undefined
+    /// //assert_eq!(None, vec.get(3));
+    /// # }
+    /// ```
+    pub unsafe fn get_unchecked_mut(&mut self, index: usize) -> &mut T {
+        self.as_mut_slice().get_unchecked_mut(index)
+    }
+
+    /// Removes the specified range from the vector, returning the removed
+    /// elements as an iterator.
+    ///
+    /// The elements after the range are shifted down when the iterator is
+    /// dropped, even if it was not fully consumed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end, or if the end
+    /// is greater than the length of the vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 10]);
+    /// let mut vec = FixedVec::new(&mut space);
+    ///
+    /// // Take the first two bytes and shift the rest down
+    /// vec.push_all(&[1, 2, 3, 4, 5]).unwrap();
+    /// let header: Vec<u8> = vec.drain(..2).collect();
+    /// assert_eq!(header, &[1, 2]);
+    /// assert_eq!(vec.as_slice(), &[3, 4, 5]);
+    ///
+    /// // A partially-consumed drain still removes the whole range
+    /// assert_eq!(vec.drain(1..).next(), Some(4));
+    /// assert_eq!(vec.as_slice(), &[3]);
+    /// # }
+    /// ```
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = resolve_range(range, self.len);
+        let ptr = self.as_mut_ptr();
+        unsafe { Drain::new(ptr, &mut self.len, start, end) }
+    }
+
+    /// Removes the elements in the specified range that match a predicate,
+    /// returning them as an iterator.
+    ///
+    /// Each element in the range is passed to `filter` once, in order. If it
+    /// returns true, the element is yielded; otherwise it is kept, and the
+    /// kept elements are compacted as the iterator advances. If the iterator
+    /// is dropped before it is exhausted, the unexamined elements are kept.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end, or if the end
+    /// is greater than the length of the vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 10]);
+    /// let mut vec = FixedVec::new(&mut space);
+    ///
+    /// vec.push_all(&[1, 2, 3, 4, 5, 6]).unwrap();
+    /// let evens: Vec<u8> = vec.extract_if(.., |x| *x % 2 == 0).collect();
+    /// assert_eq!(evens, &[2, 4, 6]);
+    /// assert_eq!(vec.as_slice(), &[1, 3, 5]);
+    /// # }
+    /// ```
+    pub fn extract_if<R, F>(&mut self, range: R, filter: F) -> ExtractIf<'_, T, F>
+    where
+        R: RangeBounds<usize>,
+        F: FnMut(&mut T) -> bool,
+    {
+        let (start, end) = resolve_range(range, self.len);
+        let ptr = self.as_mut_ptr();
+        unsafe { ExtractIf::new(ptr, &mut self.len, start, end, filter) }
+    }
+
+    /// Moves all the elements of `other` into this vector, leaving `other`
+    /// empty.
+    ///
+    /// If there is insufficient space, NO elements are moved and an error is
+    /// returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 5]);
+    /// let mut vec = FixedVec::new(&mut space);
+    /// vec.push_all(&[1, 2]).unwrap();
+    ///
+    /// let mut other_space = alloc_stack!([u8; 5]);
+    /// let mut other = FixedVec::new(&mut other_space);
+    /// other.push_all(&[3, 4]).unwrap();
+    ///
+    /// vec.append(&mut other).unwrap();
+    /// assert_eq!(vec.as_slice(), &[1, 2, 3, 4]);
+    /// assert!(other.is_empty());
+    ///
+    /// // Cannot append if there is not enough capacity
+    /// other.push_all(&[5, 6]).unwrap();
+    /// assert!(vec.append(&mut other).is_err());
+    /// assert_eq!(other.as_slice(), &[5, 6]);
+    /// # }
+    /// ```
+    pub fn append<S2>(&mut self, other: &mut FixedVec<T, S2>) -> Result<()>
+    where
+        S2: Storage<T>,
+    {
+        if other.len() > self.available() {
+            return Err(ErrorKind::NoSpace);
+        }
+        let count = other.len;
+        other.len = 0;
+        unsafe {
+            let dst = self.as_mut_ptr().add(self.len);
+            ptr::copy_nonoverlapping(other.as_ptr(), dst, count);
+        }
+        self.len += count;
+        Ok(())
+    }
+
+    /// Moves the elements from `at` onwards into `dest`, leaving this vector
+    /// with the elements `[0, at)`.
+    ///
+    /// The moved elements are appended to `dest`. If `dest` does not have
+    /// enough space for them, NO elements are moved and an error is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is greater than the vector's length.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 5]);
+    /// let mut vec = FixedVec::new(&mut space);
+    /// vec.push_all(&[1, 2, 3, 4]).unwrap();
+    ///
+    /// let mut tail_space = alloc_stack!([u8; 2]);
+    /// let mut tail = FixedVec::new(&mut tail_space);
+    ///
+    /// // The tail must fit in the destination
+    /// assert!(vec.split_off(1, &mut tail).is_err());
+    /// assert_eq!(vec.as_slice(), &[1, 2, 3, 4]);
+    ///
+    /// vec.split_off(2, &mut tail).unwrap();
+    /// assert_eq!(vec.as_slice(), &[1, 2]);
+    /// assert_eq!(tail.as_slice(), &[3, 4]);
+    /// # }
+    /// ```
+    pub fn split_off<S2>(&mut self, at: usize, dest: &mut FixedVec<T, S2>) -> Result<()>
+    where
+        S2: Storage<T>,
+    {
+        assert!(at <= self.len);
+        let count = self.len - at;
+        if count > dest.available() {
+            return Err(ErrorKind::NoSpace);
+        }
+        self.len = at;
+        unsafe {
+            let dst = dest.as_mut_ptr().add(dest.len);
+            ptr::copy_nonoverlapping(self.as_ptr().add(at), dst, count);
+        }
+        dest.len += count;
+        Ok(())
+    }
+
+    /// Appends the elements of an iterator to the vector, reporting whether
+    /// they all fit.
+    ///
+    /// On success, returns the number of elements that were added. If the
+    /// vector fills up before the iterator is exhausted, the vector is left
+    /// full and the error carries an iterator over the elements that did not
+    /// fit.
+    ///
+    /// Unlike `extend`, no elements are silently discarded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 5]);
+    /// let mut vec = FixedVec::new(&mut space);
+    ///
+    /// assert_eq!(vec.try_extend(0..3).unwrap(), 3);
+    ///
+    /// // Only two more elements fit
+    /// let leftover: Vec<u8> = vec.try_extend(3..7).unwrap_err().into_element().collect();
+    /// assert_eq!(vec.as_slice(), &[0, 1, 2, 3, 4]);
+    /// assert_eq!(leftover, &[5, 6]);
+    /// # }
+    /// ```
+    pub fn try_extend<I>(
+        &mut self,
+        iterable: I,
+    ) -> core::result::Result<usize, CapacityError<Leftover<I::IntoIter>>>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut iter = iterable.into_iter();
+        let mut count = 0;
+        while let Some(item) = iter.next() {
+            if let Err(err) = self.push(item) {
+                return Err(CapacityError::new(
+                    iter::once(err.into_element()).chain(iter),
+                ));
+            }
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Appends all elements of an iterator to the vector, or none of them.
+    ///
+    /// The length reported by the iterator is checked against the available
+    /// space up front. If the elements do not fit, NO elements are added and
+    /// the untouched iterator is handed back in the error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 5]);
+    /// let mut vec = FixedVec::new(&mut space);
+    ///
+    /// vec.extend_exact(0..3).unwrap();
+    /// assert_eq!(vec.as_slice(), &[0, 1, 2]);
+    ///
+    /// // Three more elements do not fit, so none are added
+    /// let rest = vec.extend_exact(3..6).unwrap_err().into_element();
+    /// assert_eq!(vec.as_slice(), &[0, 1, 2]);
+    /// assert_eq!(rest, 3..6);
+    /// # }
+    /// ```
+    pub fn extend_exact<I>(
+        &mut self,
+        iterable: I,
+    ) -> core::result::Result<(), CapacityError<I::IntoIter>>
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let iter = iterable.into_iter();
+        let count = iter.len();
+        if count > self.available() {
+            return Err(CapacityError::new(iter));
+        }
+        // Never trust the reported length further than the space checked.
+        for item in iter.take(count) {
+            let len = self.len;
+            unsafe { ptr::write(self.as_mut_ptr().add(len), item) };
+            self.len += 1;
+        }
+        Ok(())
+    }
+
+    /// Removes all but the first of consecutive elements in the vector that
+    /// satisfy a given equality relation.
+    ///
+    /// `same_bucket` is passed each element and the last element that was
+    /// kept before it, in that order. If it returns true, the element is
+    /// removed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 10]);
+    /// let mut vec = FixedVec::new(&mut space);
+    /// vec.push_all(&[10, 11, 20, 12, 13]).unwrap();
+    /// vec.dedup_by(|a, b| *a / 10 == *b / 10);
+    /// assert_eq!(vec.as_slice(), &[10, 20, 12]);
+    /// # }
+    /// ```
+    pub fn dedup_by<F>(&mut self, mut same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        if self.len <= 1 {
+            return;
+        }
+        // Elements are leaked rather than double-dropped if `same_bucket`
+        // panics.
+        let len = self.len;
+        self.len = 0;
+        let p = self.as_mut_ptr();
+        let mut head: usize = 1;
+        unsafe {
+            // Nothing needs to move until the first element is removed.
+            while head < len && !same_bucket(&mut *p.add(head), &mut *p.add(head - 1)) {
+                head += 1;
+            }
+            let mut tail = head - 1;
+            if head < len {
+                ptr::drop_in_place(p.add(head));
+                head += 1;
+            }
+            while head < len {
+                let cur = p.add(head);
+                if !same_bucket(&mut *cur, &mut *p.add(tail)) {
+                    tail += 1;
+                    ptr::copy_nonoverlapping(cur, p.add(tail), 1);
+                } else {
+                    ptr::drop_in_place(cur);
+                }
+                head += 1;
+            }
+            self.len = tail + 1;
+        }
+    }
+
+    /// Removes all but the first of consecutive elements in the vector that
+    /// map to the same key.
+    ///
+    /// If the vector is sorted by the key, this removes all duplicates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 10]);
+    /// let mut vec = FixedVec::new(&mut space);
+    /// vec.push_all(&[10, 11, 20, 12, 13]).unwrap();
+    /// vec.dedup_by_key(|x| *x / 10);
+    /// assert_eq!(vec.as_slice(), &[10, 20, 12]);
+    /// # }
+    /// ```
+    pub fn dedup_by_key<F, K>(&mut self, mut key: F)
+    where
+        F: FnMut(&mut T) -> K,
+        K: PartialEq,
+    {
+        self.dedup_by(|a, b| key(a) == key(b))
+    }
+
+    /// Resizes the vector in-place so that `len()` is equal to `new_len`.
+    ///
+    /// New elements (if needed) are generated by calling `f`. If `new_len` is
+    /// greater than the capacity, the vector is left unchanged and an error is
+    /// returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 5]);
+    /// let mut vec = FixedVec::new(&mut space);
+    ///
+    /// let mut next = 0;
+    /// vec.resize_with(4, || { next += 1; next }).unwrap();
+    /// assert_eq!(vec.as_slice(), &[1, 2, 3, 4]);
+    /// vec.resize_with(2, || 0).unwrap();
+    /// assert_eq!(vec.as_slice(), &[1, 2]);
+    ///
+    /// // Cannot grow past the capacity
+    /// assert!(vec.resize_with(6, || 0).is_err());
+    /// assert_eq!(vec.as_slice(), &[1, 2]);
+    /// # }
+    /// ```
+    pub fn resize_with<F>(&mut self, new_len: usize, mut f: F) -> Result<()>
+    where
+        F: FnMut() -> T,
+    {
+        if new_len > self.capacity() {
+            return Err(ErrorKind::NoSpace);
+        }
+        if new_len <= self.len {
+            self.truncate(new_len);
+        } else {
+            while self.len < new_len {
+                let len = self.len;
+                unsafe { ptr::write(self.as_mut_ptr().add(len), f()) };
+                self.len += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces the specified range in the vector with the elements of
+    /// `replace_with`, returning the removed elements as an iterator.
+    ///
+    /// The space needed is checked up front against the length reported by
+    /// `replace_with`. If the result would not fit, the vector is left
+    /// unchanged and the untouched iterator is handed back in the error.
+    ///
+    /// The replacement happens when the returned iterator is dropped, even
+    /// if it was not fully consumed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end, or if the end
+    /// is greater than the length of the vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 5]);
+    /// let mut vec = FixedVec::new(&mut space);
+    /// vec.push_all(&[1, 2, 3]).unwrap();
+    ///
+    /// let removed: Vec<u8> = vec.splice(1..2, vec![7, 8, 9]).unwrap().collect();
+    /// assert_eq!(removed, &[2]);
+    /// assert_eq!(vec.as_slice(), &[1, 7, 8, 9, 3]);
+    ///
+    /// // Cannot grow past the capacity
+    /// assert!(vec.splice(..1, vec![4, 5]).is_err());
+    /// assert_eq!(vec.as_slice(), &[1, 7, 8, 9, 3]);
+    /// # }
+    /// ```
+    pub fn splice<R, I>(
+        &mut self,
+        range: R,
+        replace_with: I,
+    ) -> core::result::Result<Splice<'_, T, I::IntoIter>, CapacityError<I::IntoIter>>
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let (start, end) = resolve_range(range, self.len);
+        let iter = replace_with.into_iter();
+        let count = iter.len();
+        if count > self.capacity() - (self.len - (end - start)) {
+            return Err(CapacityError::new(iter));
+        }
+        let ptr = self.as_mut_ptr();
+        unsafe {
+            let drain = Drain::new(ptr, &mut self.len, start, end);
+            Ok(Splice::new(drain, iter, count))
+        }
+    }
+}
+
+impl<'a, T, S> FixedVec<'a, T, S>
+where
+    S: Storage<T, Slot = T>,
+{
+    /// Passes the unused slots at the end of the vector's memory to `f`, and
+    /// appends the number of leading slots that `f` reports having filled.
+    ///
+    /// This is intended for drivers that write straight into the buffer. If
+    /// `f` returns more than the number of slots it was given, nothing is
+    /// appended and an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 8]);
+    /// let mut vec = FixedVec::new(&mut space);
+    /// vec.push(0xAA).unwrap();
+    ///
+    /// // Pretend a UART delivered three bytes
+    /// vec.fill_with(|spare| {
+    ///     spare[..3].copy_from_slice(&[1, 2, 3]);
+    ///     3
+    /// }).unwrap();
+    /// assert_eq!(vec.as_slice(), &[0xAA, 1, 2, 3]);
+    ///
+    /// // Claiming more than the available space is rejected
+    /// assert!(vec.fill_with(|spare| spare.len() + 1).is_err());
+    /// assert_eq!(vec.len(), 4);
+    /// # }
+    /// ```
+    pub fn fill_with<F>(&mut self, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut [T]) -> usize,
+    {
+        let available = self.available();
+        let filled = f(self.spare_capacity_mut());
+        if filled > available {
+            return Err(ErrorKind::IndexOutOfBounds);
+        }
+        self.len += filled;
+        Ok(())
+    }
+}
+
+impl<'a, T, S> FixedVec<'a, T, S>
+where
+    S: Storage<T, Slot = MaybeUninit<T>>,
+{
+    /// Reserves the next slot of the vector, so that the next element can be
+    /// initialised in place.
+    ///
+    /// The element is only appended once the returned guard is committed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedVec;
+    /// use std::mem::MaybeUninit;
+    ///
+    /// let mut space: [MaybeUninit<[u8; 512]>; 1] = unsafe { MaybeUninit::uninit().assume_init() };
+    /// let mut vec = FixedVec::from_uninit(&mut space);
+    ///
+    /// let slot = vec.reserve_slot().unwrap();
+    /// slot.write([1; 512]);
+    /// assert_eq!(vec.len(), 1);
+    ///
+    /// // No more space
+    /// assert!(vec.reserve_slot().is_err());
+    /// ```
+    pub fn reserve_slot(&mut self) -> Result<ReservedSlot<'_, T>> {
+        if self.is_full() {
+            return Err(ErrorKind::NoSpace);
+        }
+        let len = self.len;
+        unsafe {
+            let p = self.as_mut_ptr().add(len) as *mut MaybeUninit<T>;
+            Ok(ReservedSlot::new(p, &mut self.len))
+        }
+    }
+
+    /// Appends an element that `f` initialises in place, without building
+    /// it on the stack first.
+    ///
+    /// `f` is given the next slot of the vector and must return a reference
+    /// to the element it initialised there, such as the one returned by
+    /// `MaybeUninit::write`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` returns a reference to anything other than the slot it
+    /// was given.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedVec;
+    /// use std::mem::MaybeUninit;
+    ///
+    /// let mut space: [MaybeUninit<[u8; 512]>; 2] = unsafe { MaybeUninit::uninit().assume_init() };
+    /// let mut vec = FixedVec::from_uninit(&mut space);
+    ///
+    /// vec.push_with(|slot| slot.write([0; 512])).unwrap();
+    /// assert_eq!(vec.len(), 1);
+    /// ```
+    pub fn push_with<F>(&mut self, f: F) -> Result<&mut T>
+    where
+        F: FnOnce(&mut MaybeUninit<T>) -> &mut T,
+    {
+        let mut slot = self.reserve_slot()?;
+        let expected = slot.as_mut_ptr();
+        let initialised = f(slot.get_mut()) as *mut T;
+        assert!(initialised == expected);
+        unsafe { Ok(slot.assume_init()) }
+    }
+}
+
+impl<'a, T, S> FixedVec<'a, T, S>
+where
+    T: Clone,
+    S: Storage<T>,
+{
+    /// Copies all elements from slice `other` to this vector.
+    ///
+    /// If there is insufficient space, no elements are pushed. The payload of
+    /// the returned `CapacityError` is then not an element but the vector's
+    /// remaining capacity, the number of elements of `other` that would have
+    /// fit, as returned by `element()` and `into_element()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 5]);
+    /// let mut vec = FixedVec::new(&mut space);
+    ///
+    /// // All elements are pushed to vector
+    /// vec.push_all(&[1, 2, 3, 4]).unwrap();
+    /// assert_eq!(vec.as_slice(), &[1, 2, 3, 4]);
+    ///
+    /// // If there is insufficient space, NO values are pushed
+    /// let err = vec.push_all(&[5, 6, 7]).unwrap_err();
+    /// assert_eq!(vec.as_slice(), &[1, 2, 3, 4]);
+    ///
+    /// // The error holds the remaining capacity
+    /// let would_fit = err.into_element();
+    /// assert_eq!(would_fit, 1);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn push_all(&mut self, other: &[T]) -> core::result::Result<(), CapacityError<usize>> {
+        if other.len() > self.available() {
+            Err(CapacityError::new(self.available()))
+        } else {
+            for item in other.iter() {
+                let len = self.len;
+                unsafe { ptr::write(self.as_mut_ptr().add(len), item.clone()) };
+                self.len += 1;
+            }
+            Ok(())
+        }
+    }
+
+    /// Copies all elements from slice `other` into this vector at position
+    /// `index`, shifting all elements after it to the right.
+    ///
+    /// If there is insufficient space, NO elements are inserted and an error
+    /// is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the vector's length.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 6]);
+    /// let mut vec = FixedVec::new(&mut space);
+    ///
+    /// // Prepend a header to the payload
+    /// vec.push_all(&[7, 8, 9]).unwrap();
+    /// vec.insert_slice(0, &[1, 2]).unwrap();
+    /// assert_eq!(vec.as_slice(), &[1, 2, 7, 8, 9]);
+    ///
+    /// // If there is insufficient space, NO values are inserted
+    /// assert!(vec.insert_slice(2, &[3, 4]).is_err());
+    /// assert_eq!(vec.as_slice(), &[1, 2, 7, 8, 9]);
+    /// # }
+    /// ```
+    pub fn insert_slice(&mut self, index: usize, other: &[T]) -> Result<()> {
+        assert!(index <= self.len);
+        let count = other.len();
+        if count > self.available() {
+            return Err(ErrorKind::NoSpace);
+        }
+        // The tail is leaked rather than double-dropped if `clone` panics.
+        let tail = self.len - index;
+        self.len = index;
+        let p = self.as_mut_ptr();
+        unsafe {
+            ptr::copy(p.add(index), p.add(index + count), tail);
+            for (i, item) in other.iter().enumerate() {
+                ptr::write(p.add(index + i), item.clone());
+                self.len += 1;
+            }
+        }
+        self.len += tail;
+        Ok(())
+    }
+
+    /// Resizes the vector in-place so that `len()` is equal to `new_len`.
+    ///
+    /// New elements (if needed) are cloned from `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_len` is greater than capacity
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedVec;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 10]);
+    /// let mut vec = FixedVec::new(&mut space);
+    ///
+    /// assert_eq!(vec.len(), 0);
+    /// vec.resize(5, 255);
+    /// assert_eq!(vec.as_slice(), &[255, 255, 255, 255, 255]);
+    /// vec.resize(2, 0);
+    /// assert_eq!(vec.as_slice(), &[255, 255]);
+    /// # }
+    /// ```
+    pub fn resize(&mut self, new_len: usize, value: T) {
+        assert!(new_len <= self.capacity());
+        if new_len <= self.len {
+            self.truncate(new_len);
+        } else {
+            while self.len < new_len {
+                let len = self.len;
+                unsafe { ptr::write(self.as_mut_ptr().add(len), Clone::clone(&value)) };
+                self.len += 1;
+            }
+        }
+    }
+
+    /// Clones the elements in `src` and appends them to the end of the
+    /// vector.
+    ///
+    /// If there is insufficient space, NO elements are appended and an error
+    /// is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end, or if the end
+    /// is greater than the length of the vector.
+    ///
     /// # Example
     ///
     /// ```
     /// # #[macro_use] extern crate fixedvec;
     /// # use fixedvec::FixedVec;
     /// # fn main() {
-    /// let mut space = alloc_stack!([u8; 10]);
+    /// let mut space = alloc_stack!([u8; 8]);
     /// let mut vec = FixedVec::new(&mut space);
+    /// vec.push_all(&[1, 2, 3]).unwrap();
     ///
-    /// vec.push_all(&[10, 40, 30]).unwrap();
-    /// {
-    ///     let mut x = unsafe { vec.get_unchecked_mut(1) };
-    ///     *x = 50;
-    /// }
-    /// assert_eq!(Some(&50), vec.get(1));
+    /// vec.extend_from_within(..2).unwrap();
+    /// assert_eq!(vec.as_slice(), &[1, 2, 3, 1, 2]);
     ///
-    /// // Index beyond bounds is undefined
-    /// //assert_eq!(None, vec.get(3));
+    /// // If there is insufficient space, NO values are appended
+    /// assert!(vec.extend_from_within(..).is_err());
+    /// assert_eq!(vec.as_slice(), &[1, 2, 3, 1, 2]);
     /// # }
     /// ```
-    pub unsafe fn get_unchecked_mut(&mut self, index: usize) -> &mut T {
-        self.as_mut_slice().get_unchecked_mut(index)
+    pub fn extend_from_within<R>(&mut self, src: R) -> Result<()>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = resolve_range(src, self.len);
+        if end - start > self.available() {
+            return Err(ErrorKind::NoSpace);
+        }
+        let p = self.as_mut_ptr();
+        for i in start..end {
+            let len = self.len;
+            unsafe { ptr::write(p.add(len), (*p.add(i)).clone()) };
+            self.len += 1;
+        }
+        Ok(())
     }
 }
 
-impl<'a, T> FixedVec<'a, T>
+impl<'a, T, S> FixedVec<'a, T, S>
 where
-    T: 'a + Copy + PartialEq<T>,
+    T: PartialEq<T>,
+    S: Storage<T>,
 {
     /// Removes consecutive repeated elements in the vector in O(N) time.
     ///
@@ -829,63 +2179,77 @@ where
     /// # }
     /// ```
     pub fn dedup(&mut self) {
-        if self.len <= 1 {
-            return;
-        }
-        let mut head: usize = 1;
-        let mut tail: usize = 0;
-        loop {
-            if head >= self.len {
-                break;
-            }
-            if self.memory[head] != self.memory[tail] {
-                tail += 1;
-                self.memory[tail] = self.memory[head];
-            }
-            head += 1;
-        }
-        self.len = tail + 1;
+        self.dedup_by(|a, b| a == b)
+    }
+}
+
+impl<'a, T, S> Drop for FixedVec<'a, T, S>
+where
+    S: Storage<T>,
+{
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<'a, T, S> fmt::Debug for FixedVec<'a, T, S>
+where
+    T: fmt::Debug,
+    S: Storage<T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_slice(), f)
     }
 }
 
-impl<'a, T: Copy> IntoIterator for &'a FixedVec<'a, T> {
-    type Item = &'a T;
-    type IntoIter = Iter<'a, T>;
+impl<'a, 'b, T, S> IntoIterator for &'b FixedVec<'a, T, S>
+where
+    S: Storage<T>,
+{
+    type Item = &'b T;
+    type IntoIter = Iter<'b, T>;
 
-    fn into_iter(self) -> Iter<'a, T> {
+    fn into_iter(self) -> Iter<'b, T> {
         self.iter()
     }
 }
 
-impl<'a, T: Copy> IntoIterator for &'a mut FixedVec<'a, T> {
-    type Item = &'a mut T;
-    type IntoIter = IterMut<'a, T>;
+impl<'a, 'b, T, S> IntoIterator for &'b mut FixedVec<'a, T, S>
+where
+    S: Storage<T>,
+{
+    type Item = &'b mut T;
+    type IntoIter = IterMut<'b, T>;
 
-    fn into_iter(self) -> IterMut<'a, T> {
+    fn into_iter(self) -> IterMut<'b, T> {
         self.iter_mut()
     }
 }
 
-impl<'a, T> Hash for FixedVec<'a, T>
+impl<'a, T, S> Hash for FixedVec<'a, T, S>
 where
-    T: Copy + Hash,
+    T: Hash,
+    S: Storage<T>,
 {
     #[inline]
     fn hash<H: Hasher>(&self, state: &mut H) {
-        Hash::hash(&*self.memory, state)
+        Hash::hash(self.as_slice(), state)
     }
 }
 
-impl<'a, T> Extend<T> for FixedVec<'a, T>
+/// Elements that do not fit in the vector are silently discarded. Use
+/// `FixedVec::try_extend` or `FixedVec::extend_exact` to detect overflow.
+impl<'a, T, S> Extend<T> for FixedVec<'a, T, S>
 where
-    T: Copy,
+    S: Storage<T>,
 {
     fn extend<I: IntoIterator<Item = T>>(&mut self, iterable: I) {
         if self.available() == 0 {
             return;
         }
         for n in iterable {
-            self.memory[self.len] = n;
+            let len = self.len;
+            unsafe { ptr::write(self.as_mut_ptr().add(len), n) };
             self.len += 1;
             if self.available() == 0 {
                 break;
@@ -894,51 +2258,260 @@ where
     }
 }
 
-impl<'a, T> ops::Index<usize> for FixedVec<'a, T>
+/// Indexing accepts anything a slice does - a `usize` or any kind of range -
+/// and panics if it reaches past `len()`.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate fixedvec;
+/// # use fixedvec::FixedVec;
+/// # fn main() {
+/// let mut space = alloc_stack!([u8; 10]);
+/// let mut vec = FixedVec::new(&mut space);
+/// vec.push_all(&[1, 2, 3, 4]).unwrap();
+///
+/// assert_eq!(vec[1], 2);
+/// assert_eq!(&vec[1..3], &[2, 3]);
+/// assert_eq!(&vec[..=1], &[1, 2]);
+/// assert_eq!(&vec[2..], &[3, 4]);
+/// # }
+/// ```
+impl<'a, T, S, I> ops::Index<I> for FixedVec<'a, T, S>
+where
+    S: Storage<T>,
+    I: SliceIndex<[T]>,
+{
+    type Output = I::Output;
+
+    #[inline]
+    fn index(&self, index: I) -> &I::Output {
+        &self.as_slice()[index]
+    }
+}
+
+impl<'a, T, S, I> ops::IndexMut<I> for FixedVec<'a, T, S>
+where
+    S: Storage<T>,
+    I: SliceIndex<[T]>,
+{
+    #[inline]
+    fn index_mut(&mut self, index: I) -> &mut I::Output {
+        &mut self.as_mut_slice()[index]
+    }
+}
+
+/// A `FixedVec` dereferences to a slice of its live elements, so every slice
+/// method is available directly on the vector.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate fixedvec;
+/// # use fixedvec::FixedVec;
+/// # fn main() {
+/// let mut space = alloc_stack!([u8; 10]);
+/// let mut vec = FixedVec::new(&mut space);
+/// vec.push_all(&[3, 1, 2]).unwrap();
+///
+/// vec.sort();
+/// assert_eq!(vec.binary_search(&2), Ok(1));
+/// assert!(vec.contains(&3));
+/// vec.rotate_left(1);
+/// assert_eq!(vec.as_slice(), &[2, 3, 1]);
+/// # }
+/// ```
+impl<'a, T, S> ops::Deref for FixedVec<'a, T, S>
+where
+    S: Storage<T>,
+{
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<'a, T, S> ops::DerefMut for FixedVec<'a, T, S>
+where
+    S: Storage<T>,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<'a, T, S> AsRef<[T]> for FixedVec<'a, T, S>
+where
+    S: Storage<T>,
+{
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<'a, T, S> AsMut<[T]> for FixedVec<'a, T, S>
 where
-    T: Copy,
+    S: Storage<T>,
 {
-    type Output = T;
+    #[inline]
+    fn as_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
 
+impl<'a, T, S> Borrow<[T]> for FixedVec<'a, T, S>
+where
+    S: Storage<T>,
+{
     #[inline]
-    fn index(&self, index: usize) -> &T {
-        &(self.memory)[index]
+    fn borrow(&self) -> &[T] {
+        self.as_slice()
     }
 }
 
-impl<'a, T> ops::IndexMut<usize> for FixedVec<'a, T>
+impl<'a, T, S> BorrowMut<[T]> for FixedVec<'a, T, S>
 where
-    T: Copy,
+    S: Storage<T>,
 {
     #[inline]
-    fn index_mut(&mut self, index: usize) -> &mut T {
-        &mut (self.memory)[index]
+    fn borrow_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+/// Two vectors are equal if their live elements are equal, regardless of
+/// their capacities, lifetimes or kinds of storage.
+impl<'a, 'b, T, U, S, S2> PartialEq<FixedVec<'b, U, S2>> for FixedVec<'a, T, S>
+where
+    T: PartialEq<U>,
+    S: Storage<T>,
+    S2: Storage<U>,
+{
+    fn eq(&self, other: &FixedVec<'b, U, S2>) -> bool {
+        self.as_slice() == other.as_slice()
     }
 }
 
-impl<'a, T> PartialEq for FixedVec<'a, T>
+impl<'a, T, S> Eq for FixedVec<'a, T, S>
 where
-    T: Copy + PartialEq,
+    T: Eq,
+    S: Storage<T>,
 {
-    fn eq(&self, other: &FixedVec<'a, T>) -> bool {
-        if self.len() != other.len() {
-            return false;
+}
+
+/// Implements `PartialEq` in both directions between `FixedVec` and a
+/// slice-like type, by comparing live elements.
+macro_rules! impl_slice_eq {
+    ([$($vars:tt)*] $rhs:ty) => {
+        impl<'a, $($vars)* T, U, S> PartialEq<$rhs> for FixedVec<'a, T, S>
+        where
+            T: PartialEq<U>,
+            S: Storage<T>,
+        {
+            #[inline]
+            fn eq(&self, other: &$rhs) -> bool {
+                self[..] == other[..]
+            }
+        }
+
+        impl<'a, $($vars)* T, U, S> PartialEq<FixedVec<'a, T, S>> for $rhs
+        where
+            U: PartialEq<T>,
+            S: Storage<T>,
+        {
+            #[inline]
+            fn eq(&self, other: &FixedVec<'a, T, S>) -> bool {
+                self[..] == other[..]
+            }
         }
+    };
+}
+
+impl_slice_eq! { [] [U] }
+impl_slice_eq! { ['b,] &'b [U] }
+impl_slice_eq! { ['b,] &'b mut [U] }
+impl_slice_eq! { [const N: usize,] [U; N] }
+impl_slice_eq! { ['b, const N: usize,] &'b [U; N] }
+#[cfg(feature = "alloc")]
+impl_slice_eq! { [] alloc::vec::Vec<U> }
+
+/// Vectors are ordered lexicographically by their live elements.
+impl<'a, 'b, T, S, S2> PartialOrd<FixedVec<'b, T, S2>> for FixedVec<'a, T, S>
+where
+    T: PartialOrd,
+    S: Storage<T>,
+    S2: Storage<T>,
+{
+    #[inline]
+    fn partial_cmp(&self, other: &FixedVec<'b, T, S2>) -> Option<Ordering> {
+        PartialOrd::partial_cmp(self.as_slice(), other.as_slice())
+    }
+}
 
-        (0..self.len()).all(|i| self[i] == other[i])
+impl<'a, T, S> Ord for FixedVec<'a, T, S>
+where
+    T: Ord,
+    S: Storage<T>,
+{
+    #[inline]
+    fn cmp(&self, other: &FixedVec<'a, T, S>) -> Ordering {
+        Ord::cmp(self.as_slice(), other.as_slice())
     }
 }
 
-impl<'a, T> Eq for FixedVec<'a, T> where T: Copy + Eq {}
+#[cfg(test)]
+mod test_util {
+    use core::cell::Cell;
+    use core::cmp::Ordering;
+
+    /// Increments a shared counter when dropped. Compares by its first field
+    /// only.
+    #[derive(Clone)]
+    pub struct Droppable<'a>(pub u8, pub &'a Cell<usize>);
+
+    impl<'a> Drop for Droppable<'a> {
+        fn drop(&mut self) {
+            self.1.set(self.1.get() + 1);
+        }
+    }
+
+    impl<'a> PartialEq for Droppable<'a> {
+        fn eq(&self, other: &Droppable) -> bool {
+            self.0 == other.0
+        }
+    }
+
+    impl<'a> Eq for Droppable<'a> {}
+
+    impl<'a> PartialOrd for Droppable<'a> {
+        fn partial_cmp(&self, other: &Droppable) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl<'a> Ord for Droppable<'a> {
+        fn cmp(&self, other: &Droppable) -> Ordering {
+            self.0.cmp(&other.0)
+        }
+    }
+}
 
 #[cfg(test)]
 mod test {
-    use super::FixedVec;
+    use super::test_util::Droppable;
+    use super::{ErrorKind, FixedVec};
+    use std::cell::Cell;
     use std::collections::hash_map::DefaultHasher;
-    use std::hash::Hash;
+    use std::hash::{Hash, Hasher};
+    use std::mem::MaybeUninit;
     use std::prelude::v1::*;
 
     #[test]
+    #[allow(clippy::zero_repeat_side_effects)]
     fn test_empty_array() {
         let mut empty = alloc_stack!([u8; 0]);
         let mut vec = FixedVec::new(&mut empty);
@@ -994,12 +2567,23 @@ mod test {
         vec.swap_remove(8);
     }
 
+    #[test]
+    #[should_panic]
+    fn test_split_off_bad_index() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        let mut dest_space = alloc_stack!([u8; 10]);
+        let mut dest = FixedVec::new(&mut dest_space);
+        vec.push_all(&[1, 2, 3, 4, 5]).unwrap();
+        vec.split_off(6, &mut dest).unwrap();
+    }
+
     #[test]
     fn test_iterator() {
         let mut space = alloc_stack!([u8; 10]);
         let mut vec = FixedVec::new(&mut space);
         vec.push_all(&[1, 2, 3, 4, 5]).unwrap();
-        let result: Vec<u8> = vec.iter().map(|&x| x).collect();
+        let result: Vec<u8> = vec.iter().cloned().collect();
         assert_eq!(vec.as_slice(), &result[..]);
     }
 
@@ -1014,7 +2598,267 @@ mod test {
         let mut vec2 = FixedVec::new(&mut space2);
         let mut hasher2 = DefaultHasher::new();
         vec2.push_all(&[1, 2, 3, 4, 5]).unwrap();
-        assert_eq!(vec1.hash(&mut hasher1), vec2.hash(&mut hasher2));
+        vec1.hash(&mut hasher1);
+        vec2.hash(&mut hasher2);
+        assert_eq!(hasher1.finish(), hasher2.finish());
+    }
+
+    #[test]
+    fn test_hash_ignores_capacity_and_stale_slots() {
+        // Equal vectors must hash equally regardless of the rest of the buffer
+        let mut space1 = alloc_stack!([u8; 10]);
+        let mut vec1 = FixedVec::new(&mut space1);
+        vec1.push_all(&[1, 2, 3, 9, 9]).unwrap();
+        vec1.truncate(3);
+        let mut space2 = alloc_stack!([u8; 3]);
+        let mut vec2 = FixedVec::new(&mut space2);
+        vec2.push_all(&[1, 2, 3]).unwrap();
+
+        let mut hasher1 = DefaultHasher::new();
+        let mut hasher2 = DefaultHasher::new();
+        vec1.hash(&mut hasher1);
+        vec2.hash(&mut hasher2);
+        assert_eq!(hasher1.finish(), hasher2.finish());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_past_len() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3]).unwrap();
+        let _ = vec[5];
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_mut_past_len() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3]).unwrap();
+        vec[3] = 4;
+    }
+
+    #[test]
+    fn test_debug() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3, 4]).unwrap();
+        vec.pop();
+        assert_eq!(format!("{:?}", vec), "[1, 2, 3]");
+    }
+
+    #[test]
+    fn test_slice_traits() {
+        fn sum(values: &[u8]) -> u8 {
+            values.iter().sum()
+        }
+        fn sum_ref<V: AsRef<[u8]>>(values: V) -> u8 {
+            sum(values.as_ref())
+        }
+
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3]).unwrap();
+        assert_eq!(sum(&vec), 6);
+        assert_eq!(sum_ref(&vec), 6);
+        assert_eq!(vec.windows(2).count(), 2);
+        vec.reverse();
+        assert_eq!(&vec[..], &[3, 2, 1]);
+
+        let mut set = std::collections::HashSet::new();
+        set.insert(vec);
+        assert!(set.contains(&[3, 2, 1][..]));
+    }
+
+    #[test]
+    fn test_comparisons() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3]).unwrap();
+
+        let mut other_space = [0u8; 4];
+        let mut other = FixedVec::new(&mut other_space);
+        other.push_all(&[1, 2, 3]).unwrap();
+
+        let mut uninit_space: [MaybeUninit<u8>; 3] = unsafe { MaybeUninit::uninit().assume_init() };
+        let mut uninit = FixedVec::from_uninit(&mut uninit_space);
+        uninit.push_all(&[1, 2, 4]).unwrap();
+
+        assert_eq!(vec, other);
+        assert_ne!(vec, uninit);
+        assert_eq!(vec, [1, 2, 3]);
+        assert_eq!(vec, &[1, 2, 3]);
+        assert_eq!(vec, [1, 2, 3][..]);
+        assert_eq!(&[1, 2, 3][..], vec);
+        #[cfg(feature = "alloc")]
+        assert_eq!(vec, vec![1, 2, 3]);
+        assert!(vec != [1, 2, 3, 0]);
+
+        assert!(vec < uninit);
+        other.pop();
+        assert!(other < vec);
+        assert_eq!(vec.cmp(&vec), std::cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn test_retain_visits_each_once() {
+        let mut space = alloc_stack!([u8; 10]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3, 4, 5, 6]).unwrap();
+        let mut visited = 0;
+        vec.retain(|&x| {
+            visited += 1;
+            x % 3 != 0
+        });
+        assert_eq!(visited, 6);
+        assert_eq!(vec.as_slice(), &[1, 2, 4, 5]);
+    }
+
+    #[test]
+    fn test_retain_mut() {
+        let drops = Cell::new(0);
+        let mut space: [MaybeUninit<Droppable>; 6] = unsafe { MaybeUninit::uninit().assume_init() };
+        let mut vec = FixedVec::from_uninit(&mut space);
+        for i in 1..7 {
+            vec.push(Droppable(i, &drops)).unwrap();
+        }
+        vec.retain_mut(|x| {
+            x.0 *= 10;
+            x.0 % 20 != 0
+        });
+        assert_eq!(drops.get(), 3);
+        let values: Vec<u8> = vec.iter().map(|x| x.0).collect();
+        assert_eq!(values, &[10, 30, 50]);
+    }
+
+    #[test]
+    fn test_dedup_by_key_drops() {
+        let drops = Cell::new(0);
+        let mut space: [MaybeUninit<Droppable>; 5] = unsafe { MaybeUninit::uninit().assume_init() };
+        let mut vec = FixedVec::from_uninit(&mut space);
+        for &key in &[1, 1, 2, 2, 1] {
+            vec.push(Droppable(key, &drops)).unwrap();
+        }
+        vec.dedup_by_key(|x| x.0);
+        assert_eq!(drops.get(), 2);
+        let keys: Vec<u8> = vec.iter().map(|x| x.0).collect();
+        assert_eq!(keys, &[1, 2, 1]);
+    }
+
+    #[test]
+    fn test_extend_from_within_exact_fit() {
+        let mut space = alloc_stack!([u8; 6]);
+        let mut vec = FixedVec::new(&mut space);
+        vec.push_all(&[1, 2, 3]).unwrap();
+        vec.extend_from_within(..).unwrap();
+        assert_eq!(vec.as_slice(), &[1, 2, 3, 1, 2, 3]);
+        assert_eq!(vec.extend_from_within(0..0), Ok(()));
+        assert_eq!(vec.extend_from_within(..1), Err(ErrorKind::NoSpace));
+    }
+
+    #[test]
+    fn test_insert_slice_and_remove_range() {
+        let drops = Cell::new(0);
+        let mut space: [MaybeUninit<Droppable>; 6] = unsafe { MaybeUninit::uninit().assume_init() };
+        let mut vec = FixedVec::from_uninit(&mut space);
+        vec.push(Droppable(1, &drops)).unwrap();
+        vec.push(Droppable(5, &drops)).unwrap();
+        let block = [
+            Droppable(2, &drops),
+            Droppable(3, &drops),
+            Droppable(4, &drops),
+        ];
+        vec.insert_slice(1, &block).unwrap();
+        assert_eq!(vec.insert_slice(5, &block), Err(ErrorKind::NoSpace));
+        drop(block);
+        assert_eq!(drops.get(), 3);
+
+        let values: Vec<u8> = vec.iter().map(|x| x.0).collect();
+        assert_eq!(values, &[1, 2, 3, 4, 5]);
+        vec.remove_range(..=1);
+        vec.remove_range(3..3);
+        assert_eq!(drops.get(), 5);
+        let values: Vec<u8> = vec.iter().map(|x| x.0).collect();
+        assert_eq!(values, &[3, 4, 5]);
+    }
+
+    #[test]
+    fn test_fill_with_exact_fit() {
+        let mut vec = FixedVec::from_storage([0u8; 4]);
+        vec.push(9).unwrap();
+        assert_eq!(vec.spare_capacity_mut().len(), 3);
+        vec.fill_with(|spare| {
+            spare.copy_from_slice(&[1, 2, 3]);
+            spare.len()
+        })
+        .unwrap();
+        assert!(vec.is_full());
+        assert_eq!(vec.as_slice(), &[9, 1, 2, 3]);
+        assert!(vec.spare_capacity_mut().is_empty());
+        assert_eq!(vec.fill_with(|_| 1), Err(ErrorKind::IndexOutOfBounds));
+        assert_eq!(vec.fill_with(|_| 0), Ok(()));
+    }
+
+    #[test]
+    fn test_from_parts_round_trip() {
+        let mut space = [5u8, 6, 7, 8];
+        assert!(FixedVec::from_parts(&mut space, 5).is_err());
+        let vec = FixedVec::from_parts(&mut space, 4).unwrap();
+        assert!(vec.is_full());
+
+        let (memory, len) = vec.into_inner();
+        let mut vec = FixedVec::from_parts(memory, len - 1).unwrap();
+        assert_eq!(vec.pop(), Some(7));
+        assert_eq!(vec.into_slice(), &[5, 6]);
+        assert_eq!(space, [5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn test_macros() {
+        let mut space = alloc_stack!(uninit [String; 2]);
+        let mut vec = FixedVec::from_uninit(&mut space);
+        vec.push("a".to_string()).unwrap();
+        assert_eq!(vec.capacity(), 2);
+
+        let mut space = alloc_stack!([Option<&str>; 3], None);
+        assert_eq!(FixedVec::new(&mut space).capacity(), 3);
+
+        let strs = fixedvec![&'static str; 4];
+        assert_eq!(strs.capacity(), 4);
+        let arrays = fixedvec![[u8; 4]; 2];
+        assert_eq!(arrays.capacity(), 2);
+
+        let strings = fixedvec![2 => "a".to_string(), "b".to_string(),];
+        assert_eq!(strings.as_slice(), &["a", "b"]);
+        let empty: ::ArrayFixedVec<u8, 2> = fixedvec![2 => ];
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_alloc_static_once() {
+        fn take() -> Option<FixedVec<'static, u16>> {
+            alloc_static!([u16; 4], 7)
+        }
+
+        let handles: Vec<_> = (0..4).map(|_| std::thread::spawn(take)).collect();
+        let mut taken: Vec<_> = handles
+            .into_iter()
+            .filter_map(|h| h.join().unwrap())
+            .collect();
+        assert_eq!(taken.len(), 1);
+        assert!(take().is_none());
+
+        let vec = &mut taken[0];
+        vec.push(1).unwrap();
+        assert_eq!(vec.capacity(), 4);
+        assert_eq!(vec.as_slice(), &[1]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_fixedvec_macro_overflow() {
+        let _ = fixedvec![1 => 1, 2];
     }
 
     #[test]
@@ -1025,6 +2869,17 @@ mod test {
         assert_eq!(vec.as_slice(), &[0, 1, 2, 3, 4, 5]);
     }
 
+    #[test]
+    fn test_try_extend_exact_fit() {
+        let mut space = alloc_stack!([u8; 4]);
+        let mut vec = FixedVec::new(&mut space);
+        assert_eq!(vec.try_extend(0..4).unwrap(), 4);
+        assert_eq!(vec.try_extend(None).unwrap(), 0);
+        assert!(vec.try_extend(Some(4)).is_err());
+        assert!(vec.extend_exact(None).is_ok());
+        assert_eq!(vec.as_slice(), &[0, 1, 2, 3]);
+    }
+
     #[test]
     fn test_equal() {
         let mut space1 = alloc_stack!([u8; 10]);
@@ -1038,4 +2893,49 @@ mod test {
 
         assert_eq!(vec1, vec2);
     }
+
+    #[test]
+    fn test_drop_semantics() {
+        let drops = Cell::new(0);
+        let mut space: [MaybeUninit<Droppable>; 8] = unsafe { MaybeUninit::uninit().assume_init() };
+        {
+            let mut vec = FixedVec::from_uninit(&mut space);
+            for i in 0..8 {
+                vec.push(Droppable(i, &drops)).unwrap();
+            }
+            assert!(vec.push(Droppable(8, &drops)).is_err());
+            assert_eq!(drops.get(), 1);
+
+            drop(vec.pop());
+            drop(vec.remove(0));
+            drop(vec.swap_remove(0));
+            assert_eq!(drops.get(), 4);
+
+            vec.retain(|x| x.0 % 2 == 0);
+            assert_eq!(drops.get(), 6);
+            assert_eq!(vec.len(), 3);
+
+            vec.resize(1, Droppable(0, &drops));
+            assert_eq!(drops.get(), 9);
+            vec.resize(3, Droppable(9, &drops));
+            assert_eq!(drops.get(), 10);
+
+            vec.dedup();
+            assert_eq!(drops.get(), 11);
+            assert_eq!(vec.len(), 2);
+        }
+        assert_eq!(drops.get(), 13);
+    }
+
+    #[test]
+    fn test_clear_drops_elements() {
+        let drops = Cell::new(0);
+        let mut space: [MaybeUninit<Droppable>; 4] = unsafe { MaybeUninit::uninit().assume_init() };
+        let mut vec = FixedVec::from_uninit(&mut space);
+        vec.push(Droppable(1, &drops)).unwrap();
+        vec.push(Droppable(2, &drops)).unwrap();
+        vec.clear();
+        assert_eq!(drops.get(), 2);
+        assert!(vec.is_empty());
+    }
 }
@@ -0,0 +1,383 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015-2016 Nick Stevens <nick@bitcurry.com>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Ring buffer with a configurable overflow policy.
+
+use core::fmt;
+use core::mem::MaybeUninit;
+
+use super::{CapacityError, DequeIter, FixedDeque, Storage};
+
+/// What a `FixedRing` does when an element is pushed while it is full.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OverflowPolicy {
+    /// Refuse the new element, handing it back in a `CapacityError`.
+    Reject,
+    /// Evict the oldest element to make room for the new one.
+    OverwriteOldest,
+    /// Discard the new element, keeping the existing ones.
+    DropNewest,
+}
+
+/// The outcome of a successful `FixedRing::push`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Pushed<T> {
+    /// The element was stored without displacing another.
+    Stored,
+    /// The element was stored, and the oldest element was evicted to make
+    /// room for it.
+    Evicted(T),
+    /// The ring was full, so the element was not stored and is handed back.
+    Dropped(T),
+}
+
+impl<T> Pushed<T> {
+    /// Returns `true` if the pushed element was stored in the ring.
+    #[inline]
+    pub fn is_stored(&self) -> bool {
+        match *self {
+            Pushed::Stored | Pushed::Evicted(_) => true,
+            Pushed::Dropped(_) => false,
+        }
+    }
+}
+
+/// A first-in, first-out ring buffer that applies an `OverflowPolicy` when it
+/// is full.
+///
+/// Elements are pushed at the back and popped from the front, so iteration
+/// always yields them from oldest to newest. This suits log and sample
+/// histories, where new data should evict the oldest entry.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate fixedvec;
+/// # use fixedvec::{FixedRing, OverflowPolicy};
+/// # fn main() {
+/// let mut space = alloc_stack!([u8; 3]);
+/// let mut history = FixedRing::new(&mut space, OverflowPolicy::OverwriteOldest);
+///
+/// for sample in 1..=5 {
+///     history.push(sample).unwrap();
+/// }
+/// assert_eq!(history.iter().collect::<Vec<_>>(), vec![&3, &4, &5]);
+/// # }
+/// ```
+pub struct FixedRing<'a, T, S = &'a mut [T]>
+where
+    S: Storage<T>,
+{
+    deque: FixedDeque<'a, T, S>,
+    policy: OverflowPolicy,
+}
+
+impl<'a, T> FixedRing<'a, T>
+where
+    T: 'a + Copy,
+{
+    /// Create a new, empty `FixedRing` from the provided slice.
+    ///
+    /// Only `Copy` types can be stored in a plain slice. Use `from_uninit`
+    /// for other types.
+    pub fn new(memory: &'a mut [T], policy: OverflowPolicy) -> Self {
+        FixedRing::from_storage(memory, policy)
+    }
+}
+
+impl<'a, T> FixedRing<'a, T, &'a mut [MaybeUninit<T>]> {
+    /// Create a new, empty `FixedRing` from a slice of possibly-uninitialized
+    /// memory.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::{FixedRing, OverflowPolicy, Pushed};
+    /// # fn main() {
+    /// let mut space = alloc_stack!(uninit [String; 2]);
+    /// let mut log = FixedRing::from_uninit(&mut space, OverflowPolicy::OverwriteOldest);
+    /// log.push("boot".to_string()).unwrap();
+    /// log.push("ready".to_string()).unwrap();
+    /// assert_eq!(
+    ///     log.push("error".to_string()).unwrap(),
+    ///     Pushed::Evicted("boot".to_string())
+    /// );
+    /// # }
+    /// ```
+    pub fn from_uninit(memory: &'a mut [MaybeUninit<T>], policy: OverflowPolicy) -> Self {
+        FixedRing::from_storage(memory, policy)
+    }
+}
+
+impl<'a, T, S> FixedRing<'a, T, S>
+where
+    S: Storage<T>,
+{
+    /// Create a new, empty `FixedRing` from any kind of `Storage`.
+    pub const fn from_storage(memory: S, policy: OverflowPolicy) -> Self {
+        FixedRing {
+            deque: FixedDeque::from_storage(memory),
+            policy,
+        }
+    }
+
+    /// Returns the overflow policy.
+    #[inline]
+    pub fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    /// Changes the overflow policy for later pushes.
+    #[inline]
+    pub fn set_policy(&mut self, policy: OverflowPolicy) {
+        self.policy = policy;
+    }
+
+    /// Returns the capacity of the ring.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.deque.capacity()
+    }
+
+    /// Returns the number of elements in the ring.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.deque.len()
+    }
+
+    /// Returns `true` if the ring contains no elements.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.deque.is_empty()
+    }
+
+    /// Returns `true` if the next push will overflow.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.deque.is_full()
+    }
+
+    /// Appends an element as the newest in the ring.
+    ///
+    /// If there is space, `value` is stored and `Pushed::Stored` is returned.
+    /// Otherwise the overflow policy decides the outcome:
+    ///
+    /// * `Reject`: `value` is handed back in the error.
+    /// * `OverwriteOldest`: the oldest element is evicted and returned in
+    ///   `Pushed::Evicted`.
+    /// * `DropNewest`: `value` is not stored, and is returned in
+    ///   `Pushed::Dropped`.
+    ///
+    /// A ring with no capacity at all cannot evict anything to make room, so
+    /// under `OverwriteOldest` it returns `Pushed::Dropped` too.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::{FixedRing, OverflowPolicy, Pushed};
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 2]);
+    /// let mut ring = FixedRing::new(&mut space, OverflowPolicy::Reject);
+    /// assert_eq!(ring.push(1).unwrap(), Pushed::Stored);
+    /// assert_eq!(ring.push(2).unwrap(), Pushed::Stored);
+    /// assert_eq!(ring.push(3).unwrap_err().into_element(), 3);
+    ///
+    /// ring.set_policy(OverflowPolicy::OverwriteOldest);
+    /// assert_eq!(ring.push(3).unwrap(), Pushed::Evicted(1));
+    ///
+    /// ring.set_policy(OverflowPolicy::DropNewest);
+    /// assert_eq!(ring.push(4).unwrap(), Pushed::Dropped(4));
+    /// assert_eq!(ring.iter().collect::<Vec<_>>(), vec![&2, &3]);
+    /// # }
+    /// ```
+    pub fn push(&mut self, value: T) -> Result<Pushed<T>, CapacityError<T>> {
+        if !self.deque.is_full() {
+            self.deque.push_back(value)?;
+            return Ok(Pushed::Stored);
+        }
+        match self.policy {
+            OverflowPolicy::Reject => Err(CapacityError::new(value)),
+            OverflowPolicy::DropNewest => Ok(Pushed::Dropped(value)),
+            OverflowPolicy::OverwriteOldest => {
+                let evicted = match self.deque.pop_front() {
+                    Some(oldest) => oldest,
+                    None => return Ok(Pushed::Dropped(value)),
+                };
+                self.deque.push_back(value)?;
+                Ok(Pushed::Evicted(evicted))
+            }
+        }
+    }
+
+    /// Removes the oldest element and returns it, or `None` if the ring is
+    /// empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        self.deque.pop_front()
+    }
+
+    /// Returns a reference to the oldest element, or `None` if the ring is
+    /// empty.
+    #[inline]
+    pub fn oldest(&self) -> Option<&T> {
+        self.deque.front()
+    }
+
+    /// Returns a reference to the newest element, or `None` if the ring is
+    /// empty.
+    #[inline]
+    pub fn newest(&self) -> Option<&T> {
+        self.deque.back()
+    }
+
+    /// Returns a reference to the element at the given index, counting from
+    /// the oldest, or `None` if the index is out of bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.deque.get(index)
+    }
+
+    /// Clears the ring, removing all values.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.deque.clear()
+    }
+
+    /// Returns the elements from oldest to newest, as two slices.
+    ///
+    /// The second slice is empty unless the elements wrap around the end of
+    /// the buffer.
+    #[inline]
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        self.deque.as_slices()
+    }
+
+    /// Provides an iterator from the oldest to the newest element.
+    #[inline]
+    pub fn iter(&self) -> DequeIter<'_, T> {
+        self.deque.iter()
+    }
+}
+
+impl<'a, T, S> fmt::Debug for FixedRing<'a, T, S>
+where
+    T: fmt::Debug,
+    S: Storage<T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.deque.fmt(f)
+    }
+}
+
+impl<'a, 'b, T, S> IntoIterator for &'b FixedRing<'a, T, S>
+where
+    S: Storage<T>,
+{
+    type Item = &'b T;
+    type IntoIter = DequeIter<'b, T>;
+
+    fn into_iter(self) -> DequeIter<'b, T> {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::test_util::Droppable;
+    use super::{FixedRing, OverflowPolicy, Pushed};
+    use std::cell::Cell;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn test_overwrite_oldest_order() {
+        let mut space = alloc_stack!([u16; 4]);
+        let mut ring = FixedRing::new(&mut space, OverflowPolicy::OverwriteOldest);
+        for i in 0u16..10 {
+            let expected = match i.checked_sub(4) {
+                Some(oldest) => Pushed::Evicted(oldest),
+                None => Pushed::Stored,
+            };
+            assert_eq!(ring.push(i), Ok(expected));
+        }
+        let items: Vec<u16> = ring.iter().cloned().collect();
+        assert_eq!(items, &[6, 7, 8, 9]);
+        assert_eq!(ring.oldest(), Some(&6));
+        assert_eq!(ring.newest(), Some(&9));
+        assert_eq!(ring.pop(), Some(6));
+        assert_eq!(format!("{:?}", ring), "[7, 8, 9]");
+    }
+
+    #[test]
+    fn test_drop_newest_keeps_history() {
+        let mut space = alloc_stack!([u8; 2]);
+        let mut ring = FixedRing::new(&mut space, OverflowPolicy::DropNewest);
+        assert_eq!(ring.push(1), Ok(Pushed::Stored));
+        assert_eq!(ring.push(2), Ok(Pushed::Stored));
+        assert_eq!(ring.push(3), Ok(Pushed::Dropped(3)));
+        assert_eq!(ring.as_slices(), (&[1, 2][..], &[][..]));
+    }
+
+    #[test]
+    fn test_zero_capacity() {
+        let mut ring = FixedRing::from_storage([0u8; 0], OverflowPolicy::OverwriteOldest);
+        assert_eq!(ring.push(1), Ok(Pushed::Dropped(1)));
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn test_policy_switch_outcomes() {
+        let mut space = alloc_stack!([u8; 2]);
+        let mut ring = FixedRing::new(&mut space, OverflowPolicy::DropNewest);
+        ring.push(1).unwrap();
+        ring.push(2).unwrap();
+
+        // Equal values, but only one of them was stored
+        let dropped = ring.push(1).unwrap();
+        ring.set_policy(OverflowPolicy::OverwriteOldest);
+        let evicted = ring.push(3).unwrap();
+        assert_eq!(dropped, Pushed::Dropped(1));
+        assert_eq!(evicted, Pushed::Evicted(1));
+        assert!(!dropped.is_stored());
+        assert!(evicted.is_stored());
+        assert_eq!(ring.as_slices(), (&[2][..], &[3][..]));
+
+        ring.set_policy(OverflowPolicy::Reject);
+        assert_eq!(ring.push(4).unwrap_err().into_element(), 4);
+    }
+
+    #[test]
+    fn test_evicted_elements_dropped_once() {
+        let drops = Cell::new(0);
+        {
+            let mut space = alloc_stack!(uninit [Droppable; 2]);
+            let mut ring = FixedRing::from_uninit(&mut space, OverflowPolicy::OverwriteOldest);
+            for i in 0..5 {
+                assert!(ring.push(Droppable(i, &drops)).ok().unwrap().is_stored());
+            }
+            assert_eq!(drops.get(), 3);
+            assert_eq!(ring.oldest().map(|x| x.0), Some(3));
+        }
+        assert_eq!(drops.get(), 5);
+    }
+}
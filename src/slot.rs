@@ -0,0 +1,171 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015-2016 Nick Stevens <nick@bitcurry.com>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Guard for initialising the next element of a `FixedVec` in place.
+
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+
+/// A reserved, uninitialised slot at the end of a `FixedVec`.
+///
+/// This struct is created by `FixedVec::reserve_slot`. The vector's length
+/// only grows once the slot is committed with `write` or `assume_init`. If
+/// the guard is dropped instead, the vector is unchanged, and anything
+/// written to the slot is leaked rather than dropped.
+pub struct ReservedSlot<'b, T: 'b> {
+    /// The reserved slot.
+    ptr: *mut MaybeUninit<T>,
+    /// The vector's length, which is the index of the reserved slot.
+    len: &'b mut usize,
+    marker: PhantomData<&'b mut T>,
+}
+
+impl<'b, T> ReservedSlot<'b, T> {
+    /// Create a guard for the slot at `ptr`, which is at index `*len` of a
+    /// vector.
+    ///
+    /// The caller must ensure that `ptr` is a free slot within the vector's
+    /// capacity, and that the memory stays borrowed for `'b`.
+    pub(crate) unsafe fn new(ptr: *mut MaybeUninit<T>, len: &'b mut usize) -> Self {
+        ReservedSlot {
+            ptr,
+            len,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the reserved slot.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut MaybeUninit<T> {
+        unsafe { &mut *self.ptr }
+    }
+
+    /// Returns a raw pointer to the reserved slot, for initialising it field
+    /// by field.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr as *mut T
+    }
+
+    /// Moves `value` into the slot and appends it to the vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedVec;
+    /// use std::mem::MaybeUninit;
+    ///
+    /// let mut space: [MaybeUninit<u32>; 2] = unsafe { MaybeUninit::uninit().assume_init() };
+    /// let mut vec = FixedVec::from_uninit(&mut space);
+    ///
+    /// *vec.reserve_slot().unwrap().write(1) += 1;
+    /// assert_eq!(vec.as_slice(), &[2]);
+    /// ```
+    #[inline]
+    pub fn write(mut self, value: T) -> &'b mut T {
+        self.get_mut().write(value);
+        unsafe { self.assume_init() }
+    }
+
+    /// Appends the element that was written to the slot to the vector.
+    ///
+    /// # Safety
+    ///
+    /// The slot must hold a fully initialised `T`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fixedvec::FixedVec;
+    /// use std::mem::MaybeUninit;
+    /// use std::ptr;
+    ///
+    /// struct Frame {
+    ///     id: u32,
+    ///     data: [u8; 512],
+    /// }
+    ///
+    /// let mut space: [MaybeUninit<Frame>; 2] = unsafe { MaybeUninit::uninit().assume_init() };
+    /// let mut vec = FixedVec::from_uninit(&mut space);
+    ///
+    /// let mut slot = vec.reserve_slot().unwrap();
+    /// let frame = slot.as_mut_ptr();
+    /// unsafe {
+    ///     ptr::addr_of_mut!((*frame).id).write(7);
+    ///     ptr::addr_of_mut!((*frame).data).write_bytes(0, 1);
+    ///     slot.assume_init();
+    /// }
+    /// assert_eq!(vec[0].id, 7);
+    /// ```
+    #[inline]
+    pub unsafe fn assume_init(self) -> &'b mut T {
+        *self.len += 1;
+        &mut *(self.ptr as *mut T)
+    }
+}
+
+unsafe impl<'b, T: Send> Send for ReservedSlot<'b, T> {}
+unsafe impl<'b, T: Sync> Sync for ReservedSlot<'b, T> {}
+
+#[cfg(test)]
+mod test {
+    use super::super::FixedVec;
+    use std::mem::MaybeUninit;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn test_reserve_slot_uncommitted() {
+        let mut space: [MaybeUninit<String>; 2] = unsafe { MaybeUninit::uninit().assume_init() };
+        let mut vec = FixedVec::from_uninit(&mut space);
+        vec.push("a".to_string()).unwrap();
+        {
+            let mut slot = vec.reserve_slot().unwrap();
+            slot.get_mut().write("leaked".to_string());
+        }
+        assert_eq!(vec.as_slice(), &["a"]);
+        vec.reserve_slot().unwrap().write("b".to_string());
+        assert_eq!(vec.as_slice(), &["a", "b"]);
+        assert!(vec.reserve_slot().is_err());
+    }
+
+    #[test]
+    fn test_push_with() {
+        let mut space: [MaybeUninit<[u8; 512]>; 2] = unsafe { MaybeUninit::uninit().assume_init() };
+        let mut vec = FixedVec::from_uninit(&mut space);
+        vec.push_with(|slot| {
+            let frame = slot.write([0; 512]);
+            frame[511] = 1;
+            frame
+        })
+        .unwrap();
+        assert_eq!(vec[0][511], 1);
+        assert_eq!(vec.len(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_push_with_foreign_reference() {
+        let mut space: [MaybeUninit<u8>; 2] = unsafe { MaybeUninit::uninit().assume_init() };
+        let mut vec = FixedVec::from_uninit(&mut space);
+        let _ = vec.push_with(|_| Box::leak(Box::new(0)));
+    }
+}
@@ -0,0 +1,849 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015-2016 Nick Stevens <nick@bitcurry.com>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Ordered map over sorted storage.
+
+use core::borrow::Borrow;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::mem;
+use core::mem::MaybeUninit;
+use core::ops::RangeBounds;
+use core::slice;
+
+use super::{resolve_key_range, ErrorKind, FixedVec, Result, Storage};
+
+/// An ordered map that keeps its entries sorted by key in a `FixedVec`.
+///
+/// Lookups are binary searches, taking O(log N) time. Insertions and removals
+/// shift the entries after the affected key, taking O(N) time. This suits
+/// small lookup tables that are built once and read often.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate fixedvec;
+/// # use fixedvec::FixedSortedMap;
+/// # fn main() {
+/// let mut space = alloc_stack!([(&str, u32); 8], ("", 0));
+/// let mut registers = FixedSortedMap::new(&mut space);
+/// registers.insert("STATUS", 0x04).unwrap();
+/// registers.insert("CTRL", 0x00).unwrap();
+/// registers.insert("DATA", 0x08).unwrap();
+///
+/// assert_eq!(registers.get("DATA"), Some(&0x08));
+/// assert_eq!(
+///     registers.iter().map(|(k, _)| *k).collect::<Vec<_>>(),
+///     vec!["CTRL", "DATA", "STATUS"]
+/// );
+/// # }
+/// ```
+pub struct FixedSortedMap<'a, K, V, S = &'a mut [(K, V)]>
+where
+    K: Ord,
+    S: Storage<(K, V)>,
+{
+    entries: FixedVec<'a, (K, V), S>,
+}
+
+impl<'a, K, V> FixedSortedMap<'a, K, V>
+where
+    K: 'a + Ord + Copy,
+    V: 'a + Copy,
+{
+    /// Create a new, empty `FixedSortedMap` from the provided slice.
+    ///
+    /// Only `Copy` keys and values can be stored in a plain slice. Use
+    /// `from_uninit` for other types.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedSortedMap;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([(u8, u8); 4]);
+    /// let map = FixedSortedMap::new(&mut space);
+    /// assert_eq!(map.capacity(), 4);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn new(memory: &'a mut [(K, V)]) -> Self {
+        FixedSortedMap {
+            entries: FixedVec::new(memory),
+        }
+    }
+}
+
+impl<'a, K, V> FixedSortedMap<'a, K, V, &'a mut [MaybeUninit<(K, V)>]>
+where
+    K: Ord,
+{
+    /// Create a new, empty `FixedSortedMap` from a slice of
+    /// possibly-uninitialized memory.
+    pub fn from_uninit(memory: &'a mut [MaybeUninit<(K, V)>]) -> Self {
+        FixedSortedMap {
+            entries: FixedVec::from_uninit(memory),
+        }
+    }
+}
+
+impl<'a, K, V, S> FixedSortedMap<'a, K, V, S>
+where
+    K: Ord,
+    S: Storage<(K, V)>,
+{
+    /// Create a new, empty `FixedSortedMap` that owns `memory`.
+    pub const fn from_storage(memory: S) -> Self {
+        FixedSortedMap {
+            entries: FixedVec::from_storage(memory),
+        }
+    }
+
+    /// Returns the number of entries the map can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.entries.capacity()
+    }
+
+    /// Returns the number of entries in the map.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the map contains no entries.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns `true` if the map has no space left for more entries.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.entries.is_full()
+    }
+
+    /// Returns the entries of the map, sorted by key.
+    #[inline]
+    pub fn as_slice(&self) -> &[(K, V)] {
+        self.entries.as_slice()
+    }
+
+    /// Removes all entries from the map.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.entries.clear()
+    }
+
+    /// Returns `Ok` with the index of `key`, or `Err` with the index where it
+    /// would be inserted.
+    fn search<Q>(&self, key: &Q) -> core::result::Result<usize, usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.entries.binary_search_by(|(k, _)| k.borrow().cmp(key))
+    }
+
+    /// Returns `true` if the map contains a value for `key`.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.search(key).is_ok()
+    }
+
+    /// Returns a reference to the value for `key`, or `None` if the map does
+    /// not contain it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedSortedMap;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([(u8, char); 4], (0, ' '));
+    /// let mut map = FixedSortedMap::new(&mut space);
+    /// map.insert(1, 'a').unwrap();
+    /// assert_eq!(map.get(&1), Some(&'a'));
+    /// assert_eq!(map.get(&2), None);
+    /// # }
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_key_value(key).map(|(_, v)| v)
+    }
+
+    /// Returns the stored key and a reference to the value for `key`, or
+    /// `None` if the map does not contain it.
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.search(key) {
+            Ok(i) => {
+                let (ref k, ref v) = self.entries[i];
+                Some((k, v))
+            }
+            Err(_) => None,
+        }
+    }
+
+    /// Returns a mutable reference to the value for `key`, or `None` if the
+    /// map does not contain it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedSortedMap;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([(u8, u8); 4]);
+    /// let mut map = FixedSortedMap::new(&mut space);
+    /// map.insert(1, 10).unwrap();
+    /// *map.get_mut(&1).unwrap() += 1;
+    /// assert_eq!(map.get(&1), Some(&11));
+    /// # }
+    /// ```
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.search(key) {
+            Ok(i) => Some(&mut self.entries[i].1),
+            Err(_) => None,
+        }
+    }
+
+    /// Inserts `value` for `key`, returning the previous value if the map
+    /// already contained `key`. The stored key is left unchanged in that case.
+    ///
+    /// Returns `ErrorKind::NoSpace` if `key` is new and the map is full.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::{ErrorKind, FixedSortedMap};
+    /// # fn main() {
+    /// let mut space = alloc_stack!([(u8, u8); 2]);
+    /// let mut map = FixedSortedMap::new(&mut space);
+    /// assert_eq!(map.insert(2, 20), Ok(None));
+    /// assert_eq!(map.insert(1, 10), Ok(None));
+    /// assert_eq!(map.insert(2, 21), Ok(Some(20)));
+    ///
+    /// // Replacing a value needs no extra space, but a new key does
+    /// assert_eq!(map.insert(3, 30), Err(ErrorKind::NoSpace));
+    /// # }
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Result<Option<V>> {
+        match self.search(&key) {
+            Ok(i) => Ok(Some(mem::replace(&mut self.entries[i].1, value))),
+            Err(i) => {
+                self.entries
+                    .insert(i, (key, value))
+                    .map_err(|_| ErrorKind::NoSpace)?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Removes `key` from the map, returning its value if the map contained
+    /// it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedSortedMap;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([(u8, u8); 4]);
+    /// let mut map = FixedSortedMap::new(&mut space);
+    /// map.insert(1, 10).unwrap();
+    /// assert_eq!(map.remove(&1), Some(10));
+    /// assert_eq!(map.remove(&1), None);
+    /// # }
+    /// ```
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    /// Removes `key` from the map, returning the stored key and its value if
+    /// the map contained it.
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.search(key) {
+            Ok(i) => Some(self.entries.remove(i)),
+            Err(_) => None,
+        }
+    }
+
+    /// Retains only the entries specified by the predicate.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.entries.retain_mut(|(k, v)| f(k, v))
+    }
+
+    /// Gets the entry for `key`, for in-place insertion or modification.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedSortedMap;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([(char, u32); 8], (' ', 0));
+    /// let mut counts = FixedSortedMap::new(&mut space);
+    /// for c in "hello".chars() {
+    ///     *counts.entry(c).or_insert(0).unwrap() += 1;
+    /// }
+    /// assert_eq!(counts.get(&'l'), Some(&2));
+    /// assert_eq!(counts.len(), 4);
+    /// # }
+    /// ```
+    pub fn entry(&mut self, key: K) -> SortedEntry<'_, 'a, K, V, S> {
+        match self.search(&key) {
+            Ok(index) => SortedEntry::Occupied(SortedOccupiedEntry { map: self, index }),
+            Err(index) => SortedEntry::Vacant(SortedVacantEntry {
+                map: self,
+                key,
+                index,
+            }),
+        }
+    }
+
+    /// Provides an iterator over the entries of the map, in key order.
+    #[inline]
+    pub fn iter(&self) -> SortedMapIter<'_, K, V> {
+        SortedMapIter {
+            inner: self.entries.iter(),
+        }
+    }
+
+    /// Provides an iterator over the entries of the map, in key order, with
+    /// mutable references to the values.
+    #[inline]
+    pub fn iter_mut(&mut self) -> SortedMapIterMut<'_, K, V> {
+        SortedMapIterMut {
+            inner: self.entries.iter_mut(),
+        }
+    }
+
+    /// Provides an iterator over the entries whose keys fall within `range`,
+    /// in key order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than its end, or if both
+    /// ends are excluded and equal.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedSortedMap;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([(u8, u8); 8]);
+    /// let mut map = FixedSortedMap::new(&mut space);
+    /// for k in 0..8 {
+    ///     map.insert(k * 10, k).unwrap();
+    /// }
+    /// let keys: Vec<u8> = map.range(15..=40).map(|(k, _)| *k).collect();
+    /// assert_eq!(keys, vec![20, 30, 40]);
+    /// # }
+    /// ```
+    pub fn range<Q, R>(&self, range: R) -> SortedMapIter<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let (start, end) = resolve_key_range(&self.entries, range, |e| &e.0);
+        SortedMapIter {
+            inner: self.entries[start..end].iter(),
+        }
+    }
+
+    /// Provides an iterator over the entries whose keys fall within `range`,
+    /// in key order, with mutable references to the values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than its end, or if both
+    /// ends are excluded and equal.
+    pub fn range_mut<Q, R>(&mut self, range: R) -> SortedMapIterMut<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let (start, end) = resolve_key_range(&self.entries, range, |e| &e.0);
+        SortedMapIterMut {
+            inner: self.entries[start..end].iter_mut(),
+        }
+    }
+}
+
+impl<'a, K, V, S> fmt::Debug for FixedSortedMap<'a, K, V, S>
+where
+    K: Ord + fmt::Debug,
+    V: fmt::Debug,
+    S: Storage<(K, V)>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, 'b, K, V, S, S2> PartialEq<FixedSortedMap<'b, K, V, S2>> for FixedSortedMap<'a, K, V, S>
+where
+    K: Ord,
+    V: PartialEq,
+    S: Storage<(K, V)>,
+    S2: Storage<(K, V)>,
+{
+    fn eq(&self, other: &FixedSortedMap<'b, K, V, S2>) -> bool {
+        self.entries == other.entries
+    }
+}
+
+impl<'a, K, V, S> Eq for FixedSortedMap<'a, K, V, S>
+where
+    K: Ord,
+    V: Eq,
+    S: Storage<(K, V)>,
+{
+}
+
+impl<'a, 'b, K, V, S> IntoIterator for &'b FixedSortedMap<'a, K, V, S>
+where
+    K: Ord,
+    S: Storage<(K, V)>,
+{
+    type Item = (&'b K, &'b V);
+    type IntoIter = SortedMapIter<'b, K, V>;
+
+    fn into_iter(self) -> SortedMapIter<'b, K, V> {
+        self.iter()
+    }
+}
+
+impl<'a, 'b, K, V, S> IntoIterator for &'b mut FixedSortedMap<'a, K, V, S>
+where
+    K: Ord,
+    S: Storage<(K, V)>,
+{
+    type Item = (&'b K, &'b mut V);
+    type IntoIter = SortedMapIterMut<'b, K, V>;
+
+    fn into_iter(self) -> SortedMapIterMut<'b, K, V> {
+        self.iter_mut()
+    }
+}
+
+/// An iterator over the entries of a `FixedSortedMap`, in key order.
+///
+/// This struct is created by `FixedSortedMap::iter` and
+/// `FixedSortedMap::range`.
+pub struct SortedMapIter<'b, K: 'b, V: 'b> {
+    inner: slice::Iter<'b, (K, V)>,
+}
+
+impl<'b, K, V> Clone for SortedMapIter<'b, K, V> {
+    fn clone(&self) -> Self {
+        SortedMapIter {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'b, K, V> Iterator for SortedMapIter<'b, K, V> {
+    type Item = (&'b K, &'b V);
+
+    #[inline]
+    fn next(&mut self) -> Option<(&'b K, &'b V)> {
+        self.inner.next().map(|(k, v)| (k, v))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'b, K, V> DoubleEndedIterator for SortedMapIter<'b, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<(&'b K, &'b V)> {
+        self.inner.next_back().map(|(k, v)| (k, v))
+    }
+}
+
+impl<'b, K, V> ExactSizeIterator for SortedMapIter<'b, K, V> {}
+
+impl<'b, K, V> FusedIterator for SortedMapIter<'b, K, V> {}
+
+/// A mutable iterator over the entries of a `FixedSortedMap`, in key order.
+///
+/// This struct is created by `FixedSortedMap::iter_mut` and
+/// `FixedSortedMap::range_mut`.
+pub struct SortedMapIterMut<'b, K: 'b, V: 'b> {
+    inner: slice::IterMut<'b, (K, V)>,
+}
+
+impl<'b, K, V> Iterator for SortedMapIterMut<'b, K, V> {
+    type Item = (&'b K, &'b mut V);
+
+    #[inline]
+    fn next(&mut self) -> Option<(&'b K, &'b mut V)> {
+        self.inner.next().map(|(k, v)| (&*k, v))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'b, K, V> DoubleEndedIterator for SortedMapIterMut<'b, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<(&'b K, &'b mut V)> {
+        self.inner.next_back().map(|(k, v)| (&*k, v))
+    }
+}
+
+impl<'b, K, V> ExactSizeIterator for SortedMapIterMut<'b, K, V> {}
+
+impl<'b, K, V> FusedIterator for SortedMapIterMut<'b, K, V> {}
+
+/// A view into a single entry of a `FixedSortedMap`, which may be vacant or
+/// occupied.
+///
+/// This enum is created by `FixedSortedMap::entry`.
+pub enum SortedEntry<'b, 'a: 'b, K: 'b + Ord, V: 'b, S: 'b>
+where
+    S: Storage<(K, V)>,
+{
+    /// An entry whose key is in the map.
+    Occupied(SortedOccupiedEntry<'b, 'a, K, V, S>),
+    /// An entry whose key is not in the map.
+    Vacant(SortedVacantEntry<'b, 'a, K, V, S>),
+}
+
+impl<'b, 'a, K, V, S> SortedEntry<'b, 'a, K, V, S>
+where
+    K: Ord,
+    S: Storage<(K, V)>,
+{
+    /// Returns the key of this entry.
+    pub fn key(&self) -> &K {
+        match *self {
+            SortedEntry::Occupied(ref e) => e.key(),
+            SortedEntry::Vacant(ref e) => e.key(),
+        }
+    }
+
+    /// Inserts `default` if the entry is vacant, then returns a mutable
+    /// reference to the value.
+    ///
+    /// Returns `ErrorKind::NoSpace` if the entry is vacant and the map is
+    /// full.
+    pub fn or_insert(self, default: V) -> Result<&'b mut V> {
+        match self {
+            SortedEntry::Occupied(e) => Ok(e.into_mut()),
+            SortedEntry::Vacant(e) => e.insert(default),
+        }
+    }
+
+    /// Inserts the result of `default` if the entry is vacant, then returns a
+    /// mutable reference to the value.
+    ///
+    /// Returns `ErrorKind::NoSpace`, without calling `default`, if the entry
+    /// is vacant and the map is full.
+    pub fn or_insert_with<F>(self, default: F) -> Result<&'b mut V>
+    where
+        F: FnOnce() -> V,
+    {
+        match self {
+            SortedEntry::Occupied(e) => Ok(e.into_mut()),
+            SortedEntry::Vacant(e) => {
+                if e.map.is_full() {
+                    return Err(ErrorKind::NoSpace);
+                }
+                e.insert(default())
+            }
+        }
+    }
+
+    /// Calls `f` on the value if the entry is occupied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedSortedMap;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([(u8, u8); 4]);
+    /// let mut map = FixedSortedMap::new(&mut space);
+    /// map.entry(1).and_modify(|v| *v += 1).or_insert(10).unwrap();
+    /// map.entry(1).and_modify(|v| *v += 1).or_insert(10).unwrap();
+    /// assert_eq!(map.get(&1), Some(&11));
+    /// # }
+    /// ```
+    pub fn and_modify<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&mut V),
+    {
+        if let SortedEntry::Occupied(ref mut e) = self {
+            f(e.get_mut());
+        }
+        self
+    }
+}
+
+impl<'b, 'a, K, V, S> SortedEntry<'b, 'a, K, V, S>
+where
+    K: Ord,
+    V: Default,
+    S: Storage<(K, V)>,
+{
+    /// Inserts the default value if the entry is vacant, then returns a
+    /// mutable reference to the value.
+    ///
+    /// Returns `ErrorKind::NoSpace` if the entry is vacant and the map is
+    /// full.
+    pub fn or_default(self) -> Result<&'b mut V> {
+        self.or_insert_with(V::default)
+    }
+}
+
+/// A view into an occupied entry of a `FixedSortedMap`.
+pub struct SortedOccupiedEntry<'b, 'a: 'b, K: 'b + Ord, V: 'b, S: 'b>
+where
+    S: Storage<(K, V)>,
+{
+    map: &'b mut FixedSortedMap<'a, K, V, S>,
+    index: usize,
+}
+
+impl<'b, 'a, K, V, S> SortedOccupiedEntry<'b, 'a, K, V, S>
+where
+    K: Ord,
+    S: Storage<(K, V)>,
+{
+    /// Returns the key of this entry.
+    pub fn key(&self) -> &K {
+        &self.map.entries[self.index].0
+    }
+
+    /// Returns a reference to the value of this entry.
+    pub fn get(&self) -> &V {
+        &self.map.entries[self.index].1
+    }
+
+    /// Returns a mutable reference to the value of this entry.
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.map.entries[self.index].1
+    }
+
+    /// Converts the entry into a mutable reference to its value, with the
+    /// lifetime of the map borrow.
+    pub fn into_mut(self) -> &'b mut V {
+        &mut self.map.entries[self.index].1
+    }
+
+    /// Replaces the value of this entry, returning the old value.
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes this entry from the map, returning its value.
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    /// Removes this entry from the map, returning its key and value.
+    pub fn remove_entry(self) -> (K, V) {
+        self.map.entries.remove(self.index)
+    }
+}
+
+/// A view into a vacant entry of a `FixedSortedMap`.
+pub struct SortedVacantEntry<'b, 'a: 'b, K: 'b + Ord, V: 'b, S: 'b>
+where
+    S: Storage<(K, V)>,
+{
+    map: &'b mut FixedSortedMap<'a, K, V, S>,
+    key: K,
+    index: usize,
+}
+
+impl<'b, 'a, K, V, S> SortedVacantEntry<'b, 'a, K, V, S>
+where
+    K: Ord,
+    S: Storage<(K, V)>,
+{
+    /// Returns the key that would be used for insertion.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Takes ownership of the key.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Inserts `value` for this entry's key, returning a mutable reference to
+    /// it.
+    ///
+    /// Returns `ErrorKind::NoSpace` if the map is full.
+    pub fn insert(self, value: V) -> Result<&'b mut V> {
+        let index = self.index;
+        self.map
+            .entries
+            .insert(index, (self.key, value))
+            .map_err(|_| ErrorKind::NoSpace)?;
+        Ok(&mut self.map.entries[index].1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::test_util::Droppable;
+    use super::{ErrorKind, FixedSortedMap, SortedEntry};
+    use std::cell::Cell;
+    use std::ops::Bound::{self, Excluded, Included, Unbounded};
+    use std::prelude::v1::*;
+
+    #[test]
+    fn test_insert_keeps_order() {
+        let mut space = alloc_stack!([(u8, u8); 8]);
+        let mut map = FixedSortedMap::new(&mut space);
+        for &k in &[5, 1, 7, 3, 0, 6, 2, 4] {
+            assert_eq!(map.insert(k, k * 2), Ok(None));
+        }
+        assert!(map.is_full());
+        assert_eq!(map.insert(9, 0), Err(ErrorKind::NoSpace));
+        assert_eq!(map.insert(3, 0), Ok(Some(6)));
+        let keys: Vec<u8> = map.iter().map(|(k, _)| *k).collect();
+        assert_eq!(keys, (0..8).collect::<Vec<_>>());
+        assert_eq!(map.remove(&0), Some(0));
+        assert_eq!(map.remove(&7), Some(14));
+        assert_eq!(map.remove(&7), None);
+        assert_eq!(map.as_slice()[0], (1, 2));
+    }
+
+    #[test]
+    fn test_range_bounds() {
+        let mut space = alloc_stack!([(u8, u8); 8]);
+        let mut map = FixedSortedMap::new(&mut space);
+        for k in 0..8 {
+            map.insert(k * 2, 0).unwrap();
+        }
+        let keys = |map: &FixedSortedMap<u8, u8>, lo: Bound<u8>, hi: Bound<u8>| -> Vec<u8> {
+            map.range((lo, hi)).map(|(k, _)| *k).collect()
+        };
+        assert_eq!(keys(&map, Included(4), Excluded(10)), vec![4, 6, 8]);
+        assert_eq!(keys(&map, Excluded(4), Included(10)), vec![6, 8, 10]);
+        assert_eq!(keys(&map, Included(5), Included(9)), vec![6, 8]);
+        assert_eq!(keys(&map, Unbounded, Excluded(3)), vec![0, 2]);
+        assert_eq!(keys(&map, Excluded(14), Unbounded), Vec::<u8>::new());
+        assert_eq!(keys(&map, Included(7), Excluded(7)), Vec::<u8>::new());
+
+        for (_, v) in map.range_mut(4..8) {
+            *v += 1;
+        }
+        let values: Vec<u8> = map.iter().map(|(_, v)| *v).collect();
+        assert_eq!(values, vec![0, 0, 1, 1, 0, 0, 0, 0]);
+        assert_eq!(map.range(2..=2).rev().count(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_range_backwards() {
+        // Checked against the bounds, so this panics even with no entries
+        let mut space = alloc_stack!([(u8, ()); 4]);
+        let map = FixedSortedMap::new(&mut space);
+        map.range((Included(5), Excluded(3)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_range_excluded_equal() {
+        let mut space = alloc_stack!([(u8, ()); 4]);
+        let map = FixedSortedMap::new(&mut space);
+        map.range((Excluded(3), Excluded(3)));
+    }
+
+    #[test]
+    fn test_entry() {
+        let drops = Cell::new(0);
+        {
+            let mut space = alloc_stack!(uninit [(u8, Droppable); 2]);
+            let mut map = FixedSortedMap::from_uninit(&mut space);
+            map.entry(2)
+                .or_insert_with(|| Droppable(20, &drops))
+                .unwrap();
+            map.entry(1)
+                .or_insert_with(|| Droppable(10, &drops))
+                .unwrap();
+            // Full, so the value is never built
+            let mut built = false;
+            let res = map.entry(3).or_insert_with(|| {
+                built = true;
+                Droppable(30, &drops)
+            });
+            assert_eq!(res.err(), Some(ErrorKind::NoSpace));
+            assert!(!built);
+            match map.entry(1) {
+                SortedEntry::Occupied(mut e) => {
+                    e.get_mut().0 += 1;
+                    assert_eq!(e.remove().0, 11);
+                }
+                SortedEntry::Vacant(_) => unreachable!(),
+            }
+            assert_eq!(drops.get(), 1);
+            match map.entry(0) {
+                SortedEntry::Vacant(e) => assert_eq!(e.into_key(), 0),
+                SortedEntry::Occupied(_) => unreachable!(),
+            }
+            assert_eq!(map.len(), 1);
+            assert_eq!(map.get(&2).map(|d| d.0), Some(20));
+        }
+        assert_eq!(drops.get(), 2);
+    }
+}
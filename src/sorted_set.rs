@@ -0,0 +1,409 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015-2016 Nick Stevens <nick@bitcurry.com>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Ordered set over sorted storage.
+
+use core::borrow::Borrow;
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ops::RangeBounds;
+
+use super::{resolve_key_range, ErrorKind, FixedVec, Iter, Result, Storage};
+
+/// An ordered set that keeps its elements sorted in a `FixedVec`.
+///
+/// Lookups are binary searches, taking O(log N) time. Insertions and removals
+/// shift the elements after the affected one, taking O(N) time.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate fixedvec;
+/// # use fixedvec::FixedSortedSet;
+/// # fn main() {
+/// let mut space = alloc_stack!([u16; 8]);
+/// let mut ids = FixedSortedSet::new(&mut space);
+/// ids.insert(42).unwrap();
+/// ids.insert(7).unwrap();
+/// ids.insert(42).unwrap();
+///
+/// assert!(ids.contains(&7));
+/// assert_eq!(ids.as_slice(), &[7, 42]);
+/// # }
+/// ```
+pub struct FixedSortedSet<'a, T, S = &'a mut [T]>
+where
+    T: Ord,
+    S: Storage<T>,
+{
+    items: FixedVec<'a, T, S>,
+}
+
+impl<'a, T> FixedSortedSet<'a, T>
+where
+    T: 'a + Ord + Copy,
+{
+    /// Create a new, empty `FixedSortedSet` from the provided slice.
+    ///
+    /// Only `Copy` types can be stored in a plain slice. Use `from_uninit`
+    /// for other types.
+    pub fn new(memory: &'a mut [T]) -> Self {
+        FixedSortedSet {
+            items: FixedVec::new(memory),
+        }
+    }
+}
+
+impl<'a, T> FixedSortedSet<'a, T, &'a mut [MaybeUninit<T>]>
+where
+    T: Ord,
+{
+    /// Create a new, empty `FixedSortedSet` from a slice of
+    /// possibly-uninitialized memory.
+    pub fn from_uninit(memory: &'a mut [MaybeUninit<T>]) -> Self {
+        FixedSortedSet {
+            items: FixedVec::from_uninit(memory),
+        }
+    }
+}
+
+impl<'a, T, S> FixedSortedSet<'a, T, S>
+where
+    T: Ord,
+    S: Storage<T>,
+{
+    /// Create a new, empty `FixedSortedSet` that owns `memory`.
+    pub const fn from_storage(memory: S) -> Self {
+        FixedSortedSet {
+            items: FixedVec::from_storage(memory),
+        }
+    }
+
+    /// Returns the number of elements the set can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.items.capacity()
+    }
+
+    /// Returns the number of elements in the set.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if the set contains no elements.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns `true` if the set has no space left for more elements.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.items.is_full()
+    }
+
+    /// Returns the elements of the set, in ascending order.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        self.items.as_slice()
+    }
+
+    /// Removes all elements from the set.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.items.clear()
+    }
+
+    /// Returns `Ok` with the index of `value`, or `Err` with the index where
+    /// it would be inserted.
+    fn search<Q>(&self, value: &Q) -> core::result::Result<usize, usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.items.binary_search_by(|x| x.borrow().cmp(value))
+    }
+
+    /// Returns `true` if the set contains `value`.
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.search(value).is_ok()
+    }
+
+    /// Returns a reference to the stored element equal to `value`, or `None`
+    /// if the set does not contain it.
+    pub fn get<Q>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.search(value) {
+            Ok(i) => Some(&self.items[i]),
+            Err(_) => None,
+        }
+    }
+
+    /// Adds `value` to the set, returning `true` if it was not already
+    /// present. An existing element is left unchanged.
+    ///
+    /// Returns `ErrorKind::NoSpace` if `value` is new and the set is full.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::{ErrorKind, FixedSortedSet};
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 2]);
+    /// let mut set = FixedSortedSet::new(&mut space);
+    /// assert_eq!(set.insert(2), Ok(true));
+    /// assert_eq!(set.insert(1), Ok(true));
+    /// assert_eq!(set.insert(2), Ok(false));
+    /// assert_eq!(set.insert(3), Err(ErrorKind::NoSpace));
+    /// # }
+    /// ```
+    pub fn insert(&mut self, value: T) -> Result<bool> {
+        match self.search(&value) {
+            Ok(_) => Ok(false),
+            Err(i) => {
+                self.items
+                    .insert(i, value)
+                    .map_err(|_| ErrorKind::NoSpace)?;
+                Ok(true)
+            }
+        }
+    }
+
+    /// Removes `value` from the set, returning `true` if it was present.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedSortedSet;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 4]);
+    /// let mut set = FixedSortedSet::new(&mut space);
+    /// set.insert(1).unwrap();
+    /// assert!(set.remove(&1));
+    /// assert!(!set.remove(&1));
+    /// # }
+    /// ```
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.take(value).is_some()
+    }
+
+    /// Removes and returns the stored element equal to `value`, or returns
+    /// `None` if the set does not contain it.
+    pub fn take<Q>(&mut self, value: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.search(value) {
+            Ok(i) => Some(self.items.remove(i)),
+            Err(_) => None,
+        }
+    }
+
+    /// Retains only the elements specified by the predicate.
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.items.retain(f)
+    }
+
+    /// Provides an iterator over the elements of the set, in ascending order.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.items.iter()
+    }
+
+    /// Provides an iterator over the elements that fall within `range`, in
+    /// ascending order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than its end, or if both
+    /// ends are excluded and equal.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate fixedvec;
+    /// # use fixedvec::FixedSortedSet;
+    /// # fn main() {
+    /// let mut space = alloc_stack!([u8; 8]);
+    /// let mut set = FixedSortedSet::new(&mut space);
+    /// for x in &[1, 3, 5, 7, 9] {
+    ///     set.insert(*x).unwrap();
+    /// }
+    /// assert_eq!(set.range(2..7).as_slice(), &[3, 5]);
+    /// # }
+    /// ```
+    pub fn range<Q, R>(&self, range: R) -> Iter<'_, T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let (start, end) = resolve_key_range(&self.items, range, |x| x);
+        self.items[start..end].iter()
+    }
+}
+
+impl<'a, T, S> fmt::Debug for FixedSortedSet<'a, T, S>
+where
+    T: Ord + fmt::Debug,
+    S: Storage<T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, 'b, T, S, S2> PartialEq<FixedSortedSet<'b, T, S2>> for FixedSortedSet<'a, T, S>
+where
+    T: Ord,
+    S: Storage<T>,
+    S2: Storage<T>,
+{
+    fn eq(&self, other: &FixedSortedSet<'b, T, S2>) -> bool {
+        self.items == other.items
+    }
+}
+
+impl<'a, T, S> Eq for FixedSortedSet<'a, T, S>
+where
+    T: Ord,
+    S: Storage<T>,
+{
+}
+
+impl<'a, 'b, T, S> IntoIterator for &'b FixedSortedSet<'a, T, S>
+where
+    T: Ord,
+    S: Storage<T>,
+{
+    type Item = &'b T;
+    type IntoIter = Iter<'b, T>;
+
+    fn into_iter(self) -> Iter<'b, T> {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::test_util::Droppable;
+    use super::{ErrorKind, FixedSortedSet};
+    use std::cell::Cell;
+    use std::ops::Bound::{self, Excluded, Included, Unbounded};
+    use std::prelude::v1::*;
+
+    #[test]
+    fn test_insert_remove() {
+        let mut space = alloc_stack!(uninit [String; 4]);
+        let mut set = FixedSortedSet::from_uninit(&mut space);
+        for s in &["pear", "apple", "fig", "apple", "kiwi"] {
+            set.insert(s.to_string()).unwrap();
+        }
+        assert_eq!(set.insert("plum".to_string()), Err(ErrorKind::NoSpace));
+        assert_eq!(set.insert("fig".to_string()), Ok(false));
+        assert_eq!(set.as_slice(), &["apple", "fig", "kiwi", "pear"]);
+        assert!(set.contains("kiwi"));
+        assert_eq!(set.take("fig"), Some("fig".to_string()));
+        assert!(!set.remove("fig"));
+        let range: Vec<&str> = set
+            .range::<str, _>((Included("b"), Excluded("p")))
+            .map(|s| &s[..])
+            .collect();
+        assert_eq!(range, vec!["kiwi"]);
+        assert_eq!(format!("{:?}", set), r#"{"apple", "kiwi", "pear"}"#);
+    }
+
+    #[test]
+    fn test_range_bounds() {
+        let mut space = alloc_stack!([u8; 8]);
+        let mut set = FixedSortedSet::new(&mut space);
+        for &x in &[9, 1, 5, 3, 7] {
+            set.insert(x).unwrap();
+        }
+        let range = |lo: Bound<u8>, hi: Bound<u8>| set.range((lo, hi)).as_slice();
+        assert_eq!(range(Unbounded, Included(5)), &[1, 3, 5]);
+        assert_eq!(range(Excluded(3), Unbounded), &[5, 7, 9]);
+        assert_eq!(range(Included(9), Included(9)), &[9]);
+        assert_eq!(range(Included(4), Included(4)), &[] as &[u8]);
+        assert_eq!(range(Excluded(0), Excluded(10)), &[1, 3, 5, 7, 9]);
+        assert_eq!(set.range(..).len(), 5);
+        assert_eq!(set.get(&7), Some(&7));
+        assert_eq!(set.get(&8), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_range_backwards() {
+        let mut space = alloc_stack!([u8; 4]);
+        let set = FixedSortedSet::new(&mut space);
+        let _ = set.range((Included(6), Excluded(4)));
+    }
+
+    #[test]
+    fn test_drops() {
+        let drops = Cell::new(0);
+        {
+            let probe = |x| Droppable(x, &drops);
+            let mut space = alloc_stack!(uninit [Droppable; 4]);
+            let mut set = FixedSortedSet::from_uninit(&mut space);
+            for &x in &[3, 1, 2] {
+                assert_eq!(set.insert(probe(x)), Ok(true));
+            }
+            // Duplicates and overflowing values are dropped
+            assert_eq!(set.insert(probe(2)), Ok(false));
+            assert_eq!(drops.get(), 1);
+            assert_eq!(set.insert(probe(4)), Ok(true));
+            assert_eq!(set.insert(probe(5)), Err(ErrorKind::NoSpace));
+            assert_eq!(drops.get(), 2);
+
+            assert_eq!(set.get(&probe(1)).map(|d| d.0), Some(1));
+            assert!(set.get(&probe(9)).is_none());
+            assert_eq!(drops.get(), 4);
+
+            set.retain(|d| d.0 % 2 == 0);
+            assert_eq!(drops.get(), 6);
+            assert_eq!(set.iter().map(|d| d.0).collect::<Vec<_>>(), vec![2, 4]);
+
+            assert_eq!(set.take(&probe(4)).map(|d| d.0), Some(4));
+            assert_eq!(drops.get(), 8);
+        }
+        assert_eq!(drops.get(), 9);
+    }
+}
@@ -0,0 +1,231 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015-2016 Nick Stevens <nick@bitcurry.com>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Backing memory for `FixedVec`.
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+use core::mem::MaybeUninit;
+
+/// Memory that a `FixedVec` can store its elements in.
+///
+/// A storage is a fixed number of contiguous slots for values of type `T`.
+/// `FixedVec` keeps track of which slots hold live elements; the storage only
+/// has to hand out a pointer to the first slot and report how many there are.
+///
+/// This is implemented for borrowed slices, arrays and (with the `alloc`
+/// feature) boxed slices, of either `T` or `MaybeUninit<T>`. Storage of plain
+/// `T` requires `T: Copy`, since the vector overwrites those slots without
+/// dropping the values already in them.
+///
+/// # Safety
+///
+/// `as_ptr` and `as_mut_ptr` must return a pointer to `capacity()`
+/// contiguous, properly aligned slots of `T` that stay valid and do not move
+/// for as long as the storage is not moved, and `capacity()` must not change.
+///
+/// `Slot` must have the same layout as `T`. It may only be `T` itself if `T`
+/// is `Copy` and every slot holds a valid `T` at all times.
+///
+/// # Example
+///
+/// ```
+/// # use fixedvec::FixedVec;
+/// // An owned array, so the vector can outlive the current stack frame
+/// fn make() -> FixedVec<'static, u8, [u8; 4]> {
+///     let mut vec = FixedVec::from_storage([0; 4]);
+///     vec.push_all(&[1, 2, 3]).unwrap();
+///     vec
+/// }
+///
+/// assert_eq!(make().as_slice(), &[1, 2, 3]);
+/// ```
+pub unsafe trait Storage<T> {
+    /// The type of a single slot: `T` if every slot always holds a valid
+    /// value, `MaybeUninit<T>` otherwise.
+    type Slot;
+
+    /// Returns the number of slots in the storage.
+    fn capacity(&self) -> usize;
+
+    /// Returns a pointer to the first slot.
+    fn as_ptr(&self) -> *const T;
+
+    /// Returns a mutable pointer to the first slot.
+    fn as_mut_ptr(&mut self) -> *mut T;
+}
+
+unsafe impl<T: Copy> Storage<T> for &mut [T] {
+    type Slot = T;
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn as_ptr(&self) -> *const T {
+        (**self).as_ptr()
+    }
+
+    #[inline]
+    fn as_mut_ptr(&mut self) -> *mut T {
+        (**self).as_mut_ptr()
+    }
+}
+
+unsafe impl<T> Storage<T> for &mut [MaybeUninit<T>] {
+    type Slot = MaybeUninit<T>;
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn as_ptr(&self) -> *const T {
+        (**self).as_ptr() as *const T
+    }
+
+    #[inline]
+    fn as_mut_ptr(&mut self) -> *mut T {
+        (**self).as_mut_ptr() as *mut T
+    }
+}
+
+unsafe impl<T: Copy, const N: usize> Storage<T> for [T; N] {
+    type Slot = T;
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        N
+    }
+
+    #[inline]
+    fn as_ptr(&self) -> *const T {
+        self[..].as_ptr()
+    }
+
+    #[inline]
+    fn as_mut_ptr(&mut self) -> *mut T {
+        self[..].as_mut_ptr()
+    }
+}
+
+unsafe impl<T, const N: usize> Storage<T> for [MaybeUninit<T>; N] {
+    type Slot = MaybeUninit<T>;
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        N
+    }
+
+    #[inline]
+    fn as_ptr(&self) -> *const T {
+        self[..].as_ptr() as *const T
+    }
+
+    #[inline]
+    fn as_mut_ptr(&mut self) -> *mut T {
+        self[..].as_mut_ptr() as *mut T
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T: Copy> Storage<T> for Box<[T]> {
+    type Slot = T;
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn as_ptr(&self) -> *const T {
+        (**self).as_ptr()
+    }
+
+    #[inline]
+    fn as_mut_ptr(&mut self) -> *mut T {
+        (**self).as_mut_ptr()
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T> Storage<T> for Box<[MaybeUninit<T>]> {
+    type Slot = MaybeUninit<T>;
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn as_ptr(&self) -> *const T {
+        (**self).as_ptr() as *const T
+    }
+
+    #[inline]
+    fn as_mut_ptr(&mut self) -> *mut T {
+        (**self).as_mut_ptr() as *mut T
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::FixedVec;
+    use core::mem::MaybeUninit;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn test_array_storage() {
+        let mut vec = FixedVec::from_storage([0u8; 4]);
+        vec.push_all(&[1, 2, 3, 4]).unwrap();
+        assert!(vec.push(5).is_err());
+        assert_eq!(vec.capacity(), 4);
+        assert_eq!(vec.as_slice(), &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_uninit_slice_storage() {
+        let mut space: [MaybeUninit<String>; 2] = unsafe { MaybeUninit::uninit().assume_init() };
+        let mut vec = FixedVec::from_uninit(&mut space);
+        vec.push("a".to_string()).unwrap();
+        vec.push("b".to_string()).unwrap();
+        assert!(vec.push("c".to_string()).is_err());
+        assert_eq!(vec.as_slice(), &["a", "b"]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_boxed_storage() {
+        let mut vec = FixedVec::from_storage(vec![0u32; 3].into_boxed_slice());
+        vec.extend(1..10);
+        assert_eq!(vec.as_slice(), &[1, 2, 3]);
+
+        let boxed: Box<[MaybeUninit<String>]> = (0..2).map(|_| MaybeUninit::uninit()).collect();
+        let mut vec = FixedVec::from_storage(boxed);
+        vec.push("a".to_string()).unwrap();
+        assert_eq!(vec.capacity(), 2);
+        assert_eq!(vec.as_slice(), &["a"]);
+    }
+}